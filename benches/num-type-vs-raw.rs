@@ -27,7 +27,7 @@ mod tests {
         let five = Num::from(5);
         b.iter(|| {
             for _ in 0..100 {
-                black_box(three + five);
+                black_box(three.clone() + five.clone());
             }
         })
     }
@@ -49,7 +49,7 @@ mod tests {
         let five = Num::from(5.);
         b.iter(|| {
             for _ in 0..100 {
-                black_box(three + five);
+                black_box(three.clone() + five.clone());
             }
         })
     }
@@ -71,7 +71,7 @@ mod tests {
         let five = Num::from(5);
         b.iter(|| {
             for _ in 0..100 {
-                black_box(three / five);
+                black_box(three.clone() / five.clone());
             }
         })
     }
@@ -93,7 +93,7 @@ mod tests {
         let five = Num::from(5.);
         b.iter(|| {
             for _ in 0..100 {
-                black_box(three / five);
+                black_box(three.clone() / five.clone());
             }
         })
     }
@@ -115,7 +115,7 @@ mod tests {
         let five = Num::from(5);
         b.iter(|| {
             for _ in 0..100 {
-                black_box(fifteen / five);
+                black_box(fifteen.clone() / five.clone());
             }
         })
     }
@@ -137,7 +137,7 @@ mod tests {
         let five = Num::from(5.);
         b.iter(|| {
             for _ in 0..100 {
-                black_box(three.hypot(five));
+                black_box(three.clone().hypot(five.clone()));
             }
         })
     }
@@ -148,7 +148,7 @@ mod tests {
         let five = Num::from(5);
         b.iter(|| {
             for _ in 0..100 {
-                black_box(three.hypot(five));
+                black_box(three.clone().hypot(five.clone()));
             }
         })
     }
@@ -35,10 +35,13 @@ pub fn repl(ctx: &mut Context) -> Result<String, ReadlineError> {
                         print!("\n{}\n", include_str!("help.txt"));
                     }
                     other => match ctx.run(other) {
+                        // several values are printed one per line
                         Ok(result) => {
-                            let res = format!("{}", result);
-                            if !res.is_empty() {
-                                println!("{}", res);
+                            for val in result.into_values() {
+                                let res = format!("{}", val);
+                                if !res.is_empty() {
+                                    println!("{}", res);
+                                }
                             }
                         }
                         Err(error) => println!("{}", error),
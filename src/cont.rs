@@ -1,48 +1,145 @@
-use std::cell::RefCell;
+use std::mem;
 use std::rc::Rc;
 
-use super::Env;
+use super::proc::{CtxFn, Parameter};
+use super::{Env, Proc, SExp};
 
-type Link = Rc<RefCell<Cont>>;
+pub type Link = Rc<Cont>;
 type OptLink = Option<Link>;
 
+/// The stack of installed exception handlers, innermost first.
+pub type Handlers = Option<Rc<Handler>>;
+
+/// An exception handler installed by `with-exception-handler` or `guard`.
+pub struct Handler {
+    pub proc: SExp,
+    /// The handlers that were installed when this one was, which are
+    /// current while it runs.
+    pub outer: Handlers,
+}
+
+/// The stack of `dynamic-wind` extents the evaluator is inside, innermost
+/// first.
+pub type Winders = Option<Rc<Winder>>;
+
+/// The thunks to run on entering and leaving a `dynamic-wind` extent.
+pub struct Winder {
+    pub before: SExp,
+    pub after: SExp,
+    pub outer: Winders,
+    /// How many extents this one is nested in, counting itself.
+    pub depth: usize,
+}
+
+/// The values given to parameter objects by `parameterize`, innermost
+/// first.
+pub type Params = Option<Rc<ParamBinding>>;
+
+pub struct ParamBinding {
+    pub param: Rc<Parameter>,
+    pub value: SExp,
+    pub outer: Params,
+}
+
+/// The parts of the evaluator's state with dynamic extent, which are saved
+/// and restored along with each frame.
 #[derive(Clone, Default)]
+pub struct Dynamic {
+    pub handlers: Handlers,
+    pub winders: Winders,
+    pub params: Params,
+}
+
+/// What to do with a value once it has been computed.
+#[derive(Clone)]
+pub enum Frame {
+    /// Hand the value back to the Rust caller of `Context::eval`.
+    Halt,
+    /// The value is a procedure - apply it to these (unevaluated) arguments.
+    Operator(SExp),
+    /// The value is an argument - keep it and evaluate the rest.
+    Operand {
+        func: Proc,
+        done: Vec<SExp>,
+        rest: SExp,
+    },
+    /// Pass the value to a Rust continuation.
+    Then(Rc<CtxFn>),
+    /// Like `Then`, but the value may also be several values from `values`.
+    Values(Rc<CtxFn>),
+}
+
+/// A partial continuation: one frame of pending work, along with the
+/// environment and dynamic state it should be resumed with.
+///
+/// Frames are never mutated once they have been pushed, so a captured chain
+/// can be resumed any number of times.
 pub struct Cont {
-    cont: OptLink,
+    parent: OptLink,
     envt: Rc<Env>,
+    dynamic: Dynamic,
+    frame: Frame,
 }
 
 impl Cont {
-    pub fn into_rc(self) -> Link {
-        Rc::new(RefCell::new(self))
-    }
-
-    pub fn from(parent: &Link) -> Self {
-        let envt = parent.borrow().envt.clone();
-
+    pub fn new(frame: Frame, envt: Rc<Env>, dynamic: Dynamic, parent: OptLink) -> Self {
         Self {
-            cont: Some(parent.clone()),
+            parent,
             envt,
+            dynamic,
+            frame,
         }
     }
 
-    pub fn parent(&self) -> OptLink {
-        self.cont.clone()
+    pub fn into_rc(self) -> Link {
+        Rc::new(self)
     }
 
-    pub fn set_env(&mut self, envt: Rc<Env>) {
-        self.envt = envt;
+    pub fn parent(&self) -> OptLink {
+        self.parent.clone()
     }
 
     pub fn env(&self) -> Rc<Env> {
         self.envt.clone()
     }
 
-    pub fn push(&mut self) {
-        self.envt = Env::new(Some(self.envt.clone())).into_rc();
+    pub fn dynamic(&self) -> Dynamic {
+        self.dynamic.clone()
+    }
+
+    pub fn is_halt(&self) -> bool {
+        matches!(self.frame, Frame::Halt)
     }
 
-    pub fn pop(&mut self) {
-        self.envt = self.envt.parent().unwrap_or_default();
+    /// Take a link apart, cloning its frame only if it is shared.
+    pub fn into_parts(link: Link) -> (Frame, Rc<Env>, Dynamic, OptLink) {
+        match Rc::try_unwrap(link) {
+            Ok(mut c) => (
+                mem::replace(&mut c.frame, Frame::Halt),
+                c.envt.clone(),
+                mem::take(&mut c.dynamic),
+                c.parent.take(),
+            ),
+            Err(rc) => (rc.frame.clone(), rc.env(), rc.dynamic(), rc.parent()),
+        }
+    }
+
+    /// Iterate over this frame and all of its ancestors.
+    pub fn iter(link: &Link) -> impl Iterator<Item = Link> {
+        std::iter::successors(Some(link.clone()), |c| c.parent())
+    }
+}
+
+// long chains would otherwise be dropped recursively
+impl Drop for Cont {
+    fn drop(&mut self) {
+        let mut next = self.parent.take();
+
+        while let Some(link) = next {
+            match Rc::try_unwrap(link) {
+                Ok(mut c) => next = c.parent.take(),
+                Err(_) => break,
+            }
+        }
     }
 }
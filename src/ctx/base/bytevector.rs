@@ -0,0 +1,214 @@
+use std::convert::TryFrom;
+
+use super::super::super::primitives::Bytevector;
+use super::super::super::proc::utils::{make_binary_expr, make_ternary_expr, make_unary_expr};
+use super::super::super::Primitive::{self, Number, String as LispString, Undefined};
+use super::super::super::SExp::{self, Atom};
+use super::super::super::{Error, Num};
+use super::super::Context;
+use super::index;
+
+macro_rules! define_with {
+    ( $ctx:ident, $name:expr, $proc:expr, $tform:expr ) => {
+        $ctx.lang
+            .insert($name.to_string(), $tform($proc, Some($name)))
+    };
+}
+
+macro_rules! define {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Pure(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+fn bytevector(e: SExp) -> Result<Bytevector, Error> {
+    match e {
+        Atom(Primitive::Bytevector(b)) => Ok(b),
+        _ => Err(Error::Type {
+            expected: "bytevector",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn byte(e: SExp) -> Result<u8, Error> {
+    let b = match &e {
+        Atom(Number(Num::Int(i))) => u8::try_from(*i).ok(),
+        _ => None,
+    };
+    b.ok_or_else(|| Error::Type {
+        expected: "byte",
+        given: e.to_string(),
+    })
+}
+
+/// The optional start and end indices from an argument list, checked
+/// against a sequence of length `len`.
+fn bounds(args: impl Iterator<Item = SExp>, len: usize) -> Result<(usize, usize), Error> {
+    let mut bounds = args.map(index);
+    let start = bounds.next().transpose()?.unwrap_or(0);
+    let end = bounds.next().transpose()?.unwrap_or(len);
+
+    if end > len {
+        return Err(Error::Index { i: end });
+    }
+    if start > end {
+        return Err(Error::Index { i: start });
+    }
+    Ok((start, end))
+}
+
+fn from_bytes(bytes: Vec<u8>) -> SExp {
+    Atom(Primitive::Bytevector(bytes.into()))
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+fn is_bytevector(e: SExp) -> Result<SExp, Error> {
+    Ok(matches!(e, Atom(Primitive::Bytevector(_))).into())
+}
+
+fn make_bytevector(args: SExp) -> Result<SExp, Error> {
+    let mut args = args.into_iter();
+    let k = index(args.next().ok_or(Error::NullList)?)?;
+    let fill = args.next().map_or(Ok(0), byte)?;
+
+    Ok(from_bytes(vec![fill; k]))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn bytevector_ref(b: SExp, i: SExp) -> Result<SExp, Error> {
+    let i = index(i)?;
+    match bytevector(b)?.borrow().get(i) {
+        Some(&byte) => Ok(usize::from(byte).into()),
+        None => Err(Error::Index { i }),
+    }
+}
+
+fn bytevector_set(b: SExp, i: SExp, val: SExp) -> Result<SExp, Error> {
+    let b = bytevector(b)?;
+    let i = index(i)?;
+    let val = byte(val)?;
+
+    match b.borrow_mut().get_mut(i) {
+        Some(byte) => *byte = val,
+        None => return Err(Error::Index { i }),
+    }
+    Ok(Atom(Undefined))
+}
+
+/// `(bytevector-copy bytevector [start [end]])`
+fn bytevector_copy(args: SExp) -> Result<SExp, Error> {
+    let mut args = args.into_iter();
+    let b = bytevector(args.next().ok_or(Error::NullList)?)?;
+    let (start, end) = bounds(args, b.len())?;
+
+    let copy = b.borrow()[start..end].to_vec();
+    Ok(from_bytes(copy))
+}
+
+/// `(bytevector-copy! to at from [start [end]])`
+fn bytevector_copy_into(args: SExp) -> Result<SExp, Error> {
+    let mut args = args.into_iter();
+    let to = bytevector(args.next().ok_or(Error::NullList)?)?;
+    let at = index(args.next().ok_or(Error::NullList)?)?;
+    let from = bytevector(args.next().ok_or(Error::NullList)?)?;
+    let (start, end) = bounds(args, from.len())?;
+
+    // copy first, since `to` and `from` may be the same bytevector
+    let bytes = from.borrow()[start..end].to_vec();
+    let mut to = to.borrow_mut();
+    if at + bytes.len() > to.len() {
+        return Err(Error::Index {
+            i: at + bytes.len(),
+        });
+    }
+
+    to[at..at + bytes.len()].copy_from_slice(&bytes);
+    Ok(Atom(Undefined))
+}
+
+fn bytevector_append(args: SExp) -> Result<SExp, Error> {
+    let mut bytes = Vec::new();
+    for b in args {
+        bytes.extend_from_slice(&bytevector(b)?.borrow());
+    }
+    Ok(from_bytes(bytes))
+}
+
+/// `(utf8->string bytevector [start [end]])`
+fn utf8_to_string(args: SExp) -> Result<SExp, Error> {
+    let mut args = args.into_iter();
+    let b = bytevector(args.next().ok_or(Error::NullList)?)?;
+    let (start, end) = bounds(args, b.len())?;
+
+    let bytes = b.borrow()[start..end].to_vec();
+    match String::from_utf8(bytes) {
+        Ok(s) => Ok(s.into()),
+        Err(e) => Err(Error::Type {
+            expected: "UTF-8",
+            given: format!("{:?}", e.as_bytes()),
+        }),
+    }
+}
+
+/// `(string->utf8 string [start [end]])`
+fn string_to_utf8(args: SExp) -> Result<SExp, Error> {
+    let mut args = args.into_iter();
+    let s = match args.next().ok_or(Error::NullList)? {
+        Atom(LispString(s)) => s,
+        e => {
+            return Err(Error::Type {
+                expected: "string",
+                given: e.type_of().to_string(),
+            })
+        }
+    };
+    let chars = s.borrow().chars().collect::<Vec<_>>();
+    let (start, end) = bounds(args, chars.len())?;
+
+    Ok(from_bytes(
+        chars[start..end].iter().collect::<String>().into_bytes(),
+    ))
+}
+
+impl Context {
+    pub(super) fn bytevector(&mut self) {
+        define_with!(self, "bytevector?", is_bytevector, make_unary_expr);
+        define!(self, "make-bytevector", make_bytevector, (1, 2));
+        define!(
+            self,
+            "bytevector",
+            |args| Ok(from_bytes(
+                args.into_iter().map(byte).collect::<Result<_, _>>()?
+            )),
+            (0,)
+        );
+        define_with!(
+            self,
+            "bytevector-length",
+            |b| Ok(bytevector(b)?.len().into()),
+            make_unary_expr
+        );
+        define_with!(self, "bytevector-u8-ref", bytevector_ref, make_binary_expr);
+        define_with!(
+            self,
+            "bytevector-u8-set!",
+            bytevector_set,
+            make_ternary_expr
+        );
+        define!(self, "bytevector-copy", bytevector_copy, (1, 3));
+        define!(self, "bytevector-copy!", bytevector_copy_into, (3, 5));
+        define!(self, "bytevector-append", bytevector_append, (0,));
+
+        define!(self, "utf8->string", utf8_to_string, (1, 3));
+        define!(self, "string->utf8", string_to_utf8, (1, 3));
+    }
+}
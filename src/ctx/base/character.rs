@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use super::super::super::proc::utils::make_unary_expr;
+use super::super::super::Error;
+use super::super::super::Primitive::{Boolean, Character, Number};
+use super::super::super::SExp::{self, Atom};
+use super::super::Context;
+
+macro_rules! define_with {
+    ( $ctx:ident, $name:expr, $proc:expr, $tform:expr ) => {
+        $ctx.lang
+            .insert($name.to_string(), $tform($proc, Some($name)))
+    };
+}
+
+macro_rules! define {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Pure(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn character(e: SExp) -> Result<char, Error> {
+    match e {
+        Atom(Character(c)) => Ok(c),
+        _ => Err(Error::Type {
+            expected: "char",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+/// Lift a predicate on characters to a unary procedure.
+fn char_test(pred: fn(char) -> bool) -> impl Fn(SExp) -> Result<SExp, Error> {
+    move |e| Ok(pred(character(e)?).into())
+}
+
+/// Lift a mapping between characters to a unary procedure.
+fn char_map(f: fn(char) -> char) -> impl Fn(SExp) -> Result<SExp, Error> {
+    move |e| Ok(f(character(e)?).into())
+}
+
+/// Case conversion that only applies where it maps one character to one.
+fn upcase(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+fn downcase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+fn is_char(e: SExp) -> Result<SExp, Error> {
+    Ok(matches!(e, Atom(Character(_))).into())
+}
+
+fn char_to_integer(e: SExp) -> Result<SExp, Error> {
+    let c = character(e)?;
+    Ok(usize::try_from(u32::from(c)).unwrap_or_default().into())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn integer_to_char(e: SExp) -> Result<SExp, Error> {
+    match e {
+        Atom(Number(ref n)) if n.is_exact() && n.is_integer() && !n.clone().is_sign_negative() => {
+            u32::try_from(usize::from(n.clone()))
+                .ok()
+                .and_then(std::char::from_u32)
+                .map(SExp::from)
+                .ok_or_else(|| Error::Type {
+                    expected: "unicode scalar value",
+                    given: e.to_string(),
+                })
+        }
+        _ => Err(Error::Type {
+            expected: "exact integer",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+/// The zero of each run of ten decimal digits (general category Nd), as of
+/// Unicode 15.0.
+const DIGIT_ZEROS: [u32; 68] = [
+    0x30, 0x660, 0x6F0, 0x7C0, 0x966, 0x9E6, 0xA66, 0xAE6, 0xB66, 0xBE6, 0xC66, 0xCE6, 0xD66,
+    0xDE6, 0xE50, 0xED0, 0xF20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80, 0x1A90,
+    0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10,
+    0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0, 0x11650,
+    0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x11F50, 0x16A60, 0x16AC0,
+    0x16B50, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0, 0x1E950,
+    0x1FBF0,
+];
+
+fn digit_value(e: SExp) -> Result<SExp, Error> {
+    let c = u32::from(character(e)?);
+    Ok(DIGIT_ZEROS
+        .iter()
+        .rfind(|&&zero| zero <= c)
+        .map(|zero| c - zero)
+        .filter(|&d| d < 10)
+        .and_then(|d| i32::try_from(d).ok())
+        .map_or(Atom(Boolean(false)), SExp::from))
+}
+
+fn compare(args: SExp, fold_case: bool, in_order: fn(Ordering) -> bool) -> Result<SExp, Error> {
+    let chars = args
+        .into_iter()
+        .map(|c| character(c).map(|c| if fold_case { downcase(c) } else { c }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(chars
+        .windows(2)
+        .all(|pair| in_order(pair[0].cmp(&pair[1])))
+        .into())
+}
+
+impl Context {
+    pub(super) fn character(&mut self) {
+        define_with!(self, "char?", is_char, make_unary_expr);
+        define_with!(self, "char->integer", char_to_integer, make_unary_expr);
+        define_with!(self, "integer->char", integer_to_char, make_unary_expr);
+        define_with!(self, "digit-value", digit_value, make_unary_expr);
+
+        define_with!(self, "char-upcase", char_map(upcase), make_unary_expr);
+        define_with!(self, "char-downcase", char_map(downcase), make_unary_expr);
+        define_with!(self, "char-foldcase", char_map(downcase), make_unary_expr);
+
+        define_with!(
+            self,
+            "char-alphabetic?",
+            char_test(char::is_alphabetic),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "char-numeric?",
+            char_test(char::is_numeric),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "char-whitespace?",
+            char_test(char::is_whitespace),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "char-upper-case?",
+            char_test(char::is_uppercase),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "char-lower-case?",
+            char_test(char::is_lowercase),
+            make_unary_expr
+        );
+
+        define!(self, "char=?", |e| compare(e, false, Ordering::is_eq), (1,));
+        define!(self, "char<?", |e| compare(e, false, Ordering::is_lt), (1,));
+        define!(self, "char>?", |e| compare(e, false, Ordering::is_gt), (1,));
+        define!(
+            self,
+            "char<=?",
+            |e| compare(e, false, Ordering::is_le),
+            (1,)
+        );
+        define!(
+            self,
+            "char>=?",
+            |e| compare(e, false, Ordering::is_ge),
+            (1,)
+        );
+        define!(
+            self,
+            "char-ci=?",
+            |e| compare(e, true, Ordering::is_eq),
+            (1,)
+        );
+        define!(
+            self,
+            "char-ci<?",
+            |e| compare(e, true, Ordering::is_lt),
+            (1,)
+        );
+        define!(
+            self,
+            "char-ci>?",
+            |e| compare(e, true, Ordering::is_gt),
+            (1,)
+        );
+        define!(
+            self,
+            "char-ci<=?",
+            |e| compare(e, true, Ordering::is_le),
+            (1,)
+        );
+        define!(
+            self,
+            "char-ci>=?",
+            |e| compare(e, true, Ordering::is_ge),
+            (1,)
+        );
+    }
+}
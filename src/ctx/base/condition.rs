@@ -0,0 +1,129 @@
+use super::super::super::primitives::Condition;
+use super::super::super::proc::utils::make_unary_expr;
+use super::super::super::Error;
+use super::super::super::Primitive::{self, String as LispString};
+use super::super::super::SExp::{self, Atom};
+use super::super::Context;
+
+macro_rules! define_with {
+    ( $ctx:ident, $name:expr, $proc:expr, $tform:expr ) => {
+        $ctx.lang
+            .insert($name.to_string(), $tform($proc, Some($name)))
+    };
+}
+
+macro_rules! define {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Pure(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+fn condition(e: SExp) -> Result<Condition, Error> {
+    match e {
+        Atom(Primitive::Condition(c)) => Ok(c),
+        _ => Err(Error::Type {
+            expected: "condition",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+/// `(error message irritant ...)`: raise a new condition.
+fn error(args: SExp) -> Result<SExp, Error> {
+    let (message, irritants) = args.split_car()?;
+    let message = match message {
+        Atom(LispString(s)) => s.to_string(),
+        other => other.to_string(),
+    };
+
+    Err(Error::Raised {
+        value: Box::new(Atom(Primitive::Condition(Condition::new(
+            "simple-error",
+            message,
+            irritants,
+        )))),
+    })
+}
+
+/// `(assert obj irritant ...)`: raise a condition if `obj` is false.
+fn assert(args: SExp) -> Result<SExp, Error> {
+    let (obj, irritants) = args.split_car()?;
+    match obj {
+        Atom(Primitive::Boolean(false)) => Err(Error::Raised {
+            value: Box::new(Atom(Primitive::Condition(Condition::new(
+                "simple-error",
+                "Assertion failed".to_string(),
+                irritants,
+            )))),
+        }),
+        _ => Ok(Atom(Primitive::Void)),
+    }
+}
+
+/// Whether an object is a condition of one of the given kinds.
+#[allow(clippy::needless_pass_by_value)]
+fn is_kind(e: SExp, kinds: &[&str]) -> SExp {
+    match e {
+        Atom(Primitive::Condition(c)) => kinds.contains(&c.kind()).into(),
+        _ => false.into(),
+    }
+}
+
+impl Context {
+    pub(super) fn condition(&mut self) {
+        define!(
+            self,
+            "raise",
+            |args| Err(Error::Raised {
+                value: Box::new(args.car()?),
+            }),
+            1
+        );
+        define!(self, "error", error, (1,));
+        define!(self, "assert", assert, (1,));
+
+        define_with!(
+            self,
+            "error-object?",
+            |e| Ok(matches!(e, Atom(Primitive::Condition(_))).into()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "error-object-message",
+            |e| Ok(condition(e)?.message().into()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "error-object-irritants",
+            |e| Ok(condition(e)?.irritants()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "condition/report-string",
+            |e| Ok(condition(e)?.report_string().into()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "read-error?",
+            |e| Ok(is_kind(e, &["parse-error"])),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "file-error?",
+            |e| Ok(is_kind(e, &["file-error"])),
+            make_unary_expr
+        );
+    }
+}
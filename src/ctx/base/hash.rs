@@ -0,0 +1,220 @@
+use super::super::super::primitives::Table;
+use super::super::super::proc::utils::{make_binary_expr, make_ternary_expr, make_unary_expr};
+use super::super::super::Error;
+use super::super::super::Primitive::{HashTable, Undefined};
+use super::super::super::SExp::{self, Atom, Null};
+use super::super::Context;
+
+macro_rules! define_with {
+    ( $ctx:ident, $name:expr, $proc:expr, $tform:expr ) => {
+        $ctx.lang
+            .insert($name.to_string(), $tform($proc, Some($name)))
+    };
+}
+
+macro_rules! define {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Pure(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+macro_rules! define_ctx {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Ctx(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+fn table(e: SExp) -> Result<Table, Error> {
+    match e {
+        Atom(HashTable(t)) => Ok(t),
+        _ => Err(Error::Type {
+            expected: "hash-table",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+fn is_hash_table(e: SExp) -> Result<SExp, Error> {
+    Ok(matches!(e, Atom(HashTable(_))).into())
+}
+
+fn hash_table_set(t: SExp, key: SExp, val: SExp) -> Result<SExp, Error> {
+    table(t)?.insert(key, val);
+    Ok(Atom(Undefined))
+}
+
+/// `(hash-table-ref table key [fail [succeed]])`: call `succeed` on the value
+/// if the key is present, and the thunk `fail` if not.
+fn hash_table_ref(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let mut args = ctx.eval_args(args)?.into_iter();
+    let t = table(args.next().ok_or(Error::NullList)?)?;
+    let key = args.next().ok_or(Error::NullList)?;
+    let (fail, succeed) = (args.next(), args.next());
+
+    match (t.get(&key), fail, succeed) {
+        (Some(val), _, Some(succeed)) => ctx.call(succeed, Null.cons(val)),
+        (Some(val), _, None) => Ok(val),
+        (None, Some(fail), _) => ctx.call(fail, Null),
+        (None, None, _) => Err(Error::Type {
+            expected: "key in hash table",
+            given: key.to_string(),
+        }),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn hash_table_ref_default(t: SExp, key: SExp, default: SExp) -> Result<SExp, Error> {
+    Ok(table(t)?.get(&key).unwrap_or(default))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn hash_table_contains(t: SExp, key: SExp) -> Result<SExp, Error> {
+    Ok(table(t)?.get(&key).is_some().into())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn hash_table_delete(t: SExp, key: SExp) -> Result<SExp, Error> {
+    table(t)?.remove(&key);
+    Ok(Atom(Undefined))
+}
+
+/// `(hash-table-update! table key proc [get-default])`: replace the value
+/// for a key with the result of calling `proc` on it, using the thunk
+/// `get-default` for the old value if the key is missing.
+fn hash_table_update(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let mut args = ctx.eval_args(args)?.into_iter();
+    let t = table(args.next().ok_or(Error::NullList)?)?;
+    let key = args.next().ok_or(Error::NullList)?;
+    let proc = args.next().ok_or(Error::NullList)?;
+
+    let old = match (t.get(&key), args.next()) {
+        (Some(val), _) => val,
+        (None, Some(get_default)) => ctx.call(get_default, Null)?,
+        (None, None) => {
+            return Err(Error::Type {
+                expected: "key in hash table",
+                given: key.to_string(),
+            })
+        }
+    };
+
+    let new = ctx.call(proc, Null.cons(old))?;
+    t.insert(key, new);
+    Ok(Atom(Undefined))
+}
+
+fn hash_table_update_default(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let mut args = ctx.eval_args(args)?.into_iter();
+    let t = table(args.next().ok_or(Error::NullList)?)?;
+    let key = args.next().ok_or(Error::NullList)?;
+    let proc = args.next().ok_or(Error::NullList)?;
+    let default = args.next().ok_or(Error::NullList)?;
+
+    let old = t.get(&key).unwrap_or(default);
+    let new = ctx.call(proc, Null.cons(old))?;
+    t.insert(key, new);
+    Ok(Atom(Undefined))
+}
+
+fn hash_table_walk(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let mut args = ctx.eval_args(args)?.into_iter();
+    let t = table(args.next().ok_or(Error::NullList)?)?;
+    let proc = args.next().ok_or(Error::NullList)?;
+
+    for (key, val) in t.entries() {
+        ctx.call(proc.clone(), Null.cons(val).cons(key))?;
+    }
+
+    Ok(Atom(Undefined))
+}
+
+impl Context {
+    pub(super) fn hash_table(&mut self) {
+        define!(
+            self,
+            "make-equal-hash-table",
+            |_| Ok(Atom(HashTable(Table::new(true)))),
+            (0, 1)
+        );
+        define!(
+            self,
+            "make-strong-eqv-hash-table",
+            |_| Ok(Atom(HashTable(Table::new(false)))),
+            (0, 1)
+        );
+        define_with!(self, "hash-table?", is_hash_table, make_unary_expr);
+
+        define_with!(self, "hash-table-set!", hash_table_set, make_ternary_expr);
+        define_ctx!(self, "hash-table-ref", hash_table_ref, (2, 4));
+        define_with!(
+            self,
+            "hash-table-ref/default",
+            hash_table_ref_default,
+            make_ternary_expr
+        );
+        define_with!(
+            self,
+            "hash-table-contains?",
+            hash_table_contains,
+            make_binary_expr
+        );
+        define_with!(
+            self,
+            "hash-table-delete!",
+            hash_table_delete,
+            make_binary_expr
+        );
+        define_ctx!(self, "hash-table-update!", hash_table_update, (3, 4));
+        define_ctx!(
+            self,
+            "hash-table-update!/default",
+            hash_table_update_default,
+            4
+        );
+
+        define_with!(
+            self,
+            "hash-table-count",
+            |t| Ok(table(t)?.len().into()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "hash-table-keys",
+            |t| Ok(table(t)?.entries().into_iter().map(|(k, _)| k).collect()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "hash-table-values",
+            |t| Ok(table(t)?.entries().into_iter().map(|(_, v)| v).collect()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "hash-table->alist",
+            |t| Ok(table(t)?
+                .entries()
+                .into_iter()
+                .map(|(k, v)| v.cons(k))
+                .collect()),
+            make_unary_expr
+        );
+        define_ctx!(self, "hash-table-walk", hash_table_walk, 2);
+    }
+}
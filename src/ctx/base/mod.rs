@@ -1,19 +1,26 @@
 use std::fmt::Write;
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs;
+use std::rc::Rc;
 
 use super::super::Primitive::{
-    Boolean, Character, Env, Number, Procedure, String as LispString, Symbol, Undefined, Void,
+    Boolean, Character, Env, Number, Procedure, String as LispString, Undefined, Values, Void,
 };
 use super::super::SExp::{self, Atom, Null, Pair};
 use super::super::{Error, Num, Result};
 
 use super::super::proc::utils::{
-    make_binary_expr, make_binary_numeric, make_fold_from0_numeric, make_fold_numeric,
-    make_unary_expr, make_unary_numeric,
+    make_binary_expr, make_binary_numeric, make_fold_numeric, make_unary_expr, make_unary_numeric,
 };
-use super::Context;
-
+use super::super::proc::{Func, Parameter, Proc};
+use super::{application, Context};
+
+mod bytevector;
+mod character;
+mod condition;
+mod hash;
+mod promise;
+mod string;
 mod tests;
 mod vec;
 
@@ -50,15 +57,6 @@ macro_rules! define {
     };
 }
 
-fn unescape(s: &str) -> String {
-    s.replace("\\n", "\n")
-        .replace("\\t", "\t")
-        .replace("\\\\", "\\")
-        .replace("\\r", "\r")
-        .replace("\\0", "\0")
-        .replace("\\\"", "\"")
-}
-
 impl Context {
     /// Base context - defines a number of useful functions and constants for
     /// use in the runtime.
@@ -84,6 +82,12 @@ impl Context {
         ret.std();
         ret.num_base();
         ret.vector();
+        ret.string();
+        ret.character();
+        ret.hash_table();
+        ret.bytevector();
+        ret.condition();
+        ret.promise();
 
         // Procedures
         define_with!(
@@ -108,23 +112,11 @@ impl Context {
         );
 
         // Strings
-        define!(
-            ret,
-            "string->list",
-            |e| match &e[0] {
-                Atom(LispString(s)) => Ok(s.chars().map(SExp::from).collect()),
-                exp => Err(Error::Type {
-                    expected: "string",
-                    given: exp.type_of().to_string()
-                }),
-            },
-            3
-        );
         define!(
             ret,
             "list->string",
             |e| match e {
-                Pair { .. } => {
+                Pair { .. } if e.len().is_ok() => {
                     match e.into_iter().fold(Ok(String::new()), |s, e| match e {
                         Atom(Character(ref c)) => {
                             if let Ok(st) = s {
@@ -140,7 +132,7 @@ impl Context {
                             given: e.type_of().to_string(),
                         }),
                     }) {
-                        Ok(s) => Ok(Atom(LispString(s))),
+                        Ok(s) => Ok(Atom(LispString(s.into()))),
                         Err(err) => Err(err),
                     }
                 }
@@ -157,28 +149,38 @@ impl Context {
 
     #[allow(clippy::too_many_lines)]
     fn std(&mut self) {
-        define!(self, "eq?", |e| Ok((e[0] == e[1]).into()), 2);
+        define_with!(
+            self,
+            "eq?",
+            |e0, e1| Ok(e0.eqv(&e1).into()),
+            make_binary_expr
+        );
         define_with!(
             self,
             "eqv?",
-            |e0, e1| Ok(match (e0, e1) {
-                (Null, Null) => true,
-                (Atom(Boolean(b0)), Atom(Boolean(b1))) => b0 == b1,
-                (Atom(Character(c0)), Atom(Character(c1))) => c0 == c1,
-                (Atom(Symbol(s0)), Atom(Symbol(s1))) => s0 == s1,
-                (Atom(Number(n0)), Atom(Number(n1))) => n0 == n1,
-                (Atom(Procedure(p0)), Atom(Procedure(p1))) => p0 == p1,
-                _ => false,
-            }
-            .into()),
+            |e0, e1| Ok(e0.eqv(&e1).into()),
+            make_binary_expr
+        );
+        define_with!(
+            self,
+            "equal?",
+            |e0, e1| Ok(e0.equal(&e1).into()),
             make_binary_expr
         );
-        define!(self, "equal?", |e| Ok((e[0] == e[1]).into()), 2);
 
         define!(self, "null?", |e| Ok((e == ((),).into()).into()), 1);
         self.lang.insert("null".to_string(), Null);
         define!(self, "void", |_| Ok(Atom(Void)), 0);
         define!(self, "list", Ok, (0,));
+        define!(
+            self,
+            "values",
+            |e| match e {
+                Pair(p) if p.tail().is_empty() => Ok(p.car()),
+                vals => Ok(Atom(Values(vals.into_iter().collect()))),
+            },
+            (0,)
+        );
         define!(self, "not", |e| Ok((e == (false,).into()).into()), 1);
 
         define!(
@@ -195,55 +197,8 @@ impl Context {
         define_with!(self, "car", SExp::car, make_unary_expr);
         define_with!(self, "cdr", SExp::cdr, make_unary_expr);
 
-        define_ctx!(
-            self,
-            "set-car!",
-            |c, e| {
-                let (car, cdr) = e.split_car()?;
-                let new = cdr.car()?;
-
-                match car {
-                    Atom(Symbol(key)) => {
-                        if let Some(mut val) = c.get(&key) {
-                            val.set_car(c.eval(new)?)?;
-                            c.set(&key, val)
-                        } else {
-                            Err(Error::UndefinedSymbol { sym: key })
-                        }
-                    }
-                    other => Err(Error::Type {
-                        expected: "symbol",
-                        given: other.type_of().to_string(),
-                    }),
-                }
-            },
-            2
-        );
-
-        define_ctx!(
-            self,
-            "set-cdr!",
-            |c, e| {
-                let (car, cdr) = e.split_car()?;
-                let new = cdr.car()?;
-
-                match car {
-                    Atom(Symbol(key)) => {
-                        if let Some(mut val) = c.get(&key) {
-                            val.set_cdr(c.eval(new)?)?;
-                            c.set(&key, val)
-                        } else {
-                            Err(Error::UndefinedSymbol { sym: key })
-                        }
-                    }
-                    other => Err(Error::Type {
-                        expected: "symbol",
-                        given: other.type_of().to_string(),
-                    }),
-                }
-            },
-            2
-        );
+        define_with!(self, "set-car!", |p, new| p.set_car(new), make_binary_expr);
+        define_with!(self, "set-cdr!", |p, new| p.set_cdr(new), make_binary_expr);
 
         define_with!(
             self,
@@ -272,13 +227,16 @@ impl Context {
         define_ctx!(
             self,
             "require",
-            |c, e| match c.eval(e.car()?)? {
-                Atom(LispString(f_name)) => c.run(&fs::read_to_string(f_name)?),
+            |c, e| Ok(c.eval_then(e.car()?, |c, f_name| match f_name {
+                Atom(LispString(f_name)) => {
+                    let code = fs::read_to_string(&*f_name.borrow())?.parse::<SExp>()?;
+                    Ok(c.defer(code))
+                }
                 other => Err(Error::Type {
                     expected: "string",
                     given: other.type_of().to_string(),
                 }),
-            },
+            })),
             1
         );
 
@@ -288,6 +246,7 @@ impl Context {
         define_ctx!(self, "filter", Self::eval_filter, 2);
 
         // procedures
+        define_ctx!(self, "make-parameter", Self::make_parameter, (1, 2));
         define_with!(
             self,
             "procedure-arity",
@@ -342,53 +301,112 @@ impl Context {
     }
 
     fn do_print(&mut self, expr: SExp, newline: bool, debug: bool) -> Result {
-        let ending = if newline { "\n" } else { "" };
-        let hevl = self.eval(expr.car()?)?;
-        let unescaped = unescape(&if debug {
-            format!("{:?}{}", hevl, ending)
-        } else {
-            format!("{}{}", hevl, ending)
-        });
-        write!(self, "{}", unescaped)?;
-
-        Ok(Atom(Undefined))
+        Ok(self.eval_then(expr.car()?, move |c, hevl| {
+            let ending = if newline { "\n" } else { "" };
+            if debug {
+                write!(c, "{:?}{}", hevl, ending)?;
+            } else {
+                write!(c, "{}{}", hevl, ending)?;
+            }
+
+            Ok(Atom(Undefined))
+        }))
+    }
+
+    /// `(make-parameter value [converter])`: make a parameter object whose
+    /// value is `value`, passed through `converter` if one is given.
+    fn make_parameter(&mut self, expr: SExp) -> Result {
+        self.eval_list(expr, |c, args| {
+            let (value, rest) = args.split_car()?;
+            match rest {
+                Pair(p) => {
+                    let converter = p.car();
+                    let call = application(converter.clone(), Null.cons(value));
+                    Ok(c.eval_then(call, move |_, init| {
+                        Ok(parameter(init, Some(converter.clone())))
+                    }))
+                }
+                _ => Ok(parameter(value, None)),
+            }
+        })
     }
 
     fn eval_map(&mut self, expr: SExp) -> Result {
-        let (head, tail) = expr.split_car()?;
-        self.eval(tail.car()?)?
-            .into_iter()
-            .map(|e| self.eval(Null.cons(e).cons(head.to_owned())))
-            .collect()
+        self.eval_list(expr, |c, args| {
+            let (proc, rest) = args.split_car()?;
+            let list = rest.car()?;
+            list.len()?;
+            Ok(c.map_from(proc, list, Null))
+        })
+    }
+
+    /// Call `proc` on each element of `rest` in turn, without growing the
+    /// Rust stack. The results so far are kept in `done`, in reverse.
+    fn map_from(&mut self, proc: SExp, rest: SExp, done: SExp) -> SExp {
+        let (head, tail) = match rest {
+            Pair(p) => p.into_parts(),
+            _ => return reverse(done),
+        };
+
+        let call = application(proc.clone(), Null.cons(head));
+        self.eval_then(call, move |c, val| {
+            Ok(c.map_from(proc.clone(), tail.clone(), done.clone().cons(val)))
+        })
     }
 
     fn eval_fold(&mut self, expr: SExp) -> Result {
-        let (head, tail) = expr.split_car()?;
-        let (init, tail) = tail.split_car()?;
-
-        self.eval(tail.car()?)?
-            .into_iter()
-            .fold(Ok(init), |a, e| match a {
-                Ok(acc) => self.eval(Null.cons(e).cons(acc).cons(head.to_owned())),
-                err => err,
-            })
+        self.eval_list(expr, |c, args| {
+            let (proc, rest) = args.split_car()?;
+            let (init, rest) = rest.split_car()?;
+            let list = rest.car()?;
+            list.len()?;
+            Ok(c.fold_from(proc, init, list))
+        })
+    }
+
+    fn fold_from(&mut self, proc: SExp, acc: SExp, rest: SExp) -> SExp {
+        let (head, tail) = match rest {
+            Pair(p) => p.into_parts(),
+            _ => return acc,
+        };
+
+        let call = application(proc.clone(), Null.cons(head).cons(acc));
+        // the last call is in tail position
+        if tail.is_empty() {
+            return self.defer(call);
+        }
+
+        self.eval_then(call, move |c, acc| {
+            Ok(c.fold_from(proc.clone(), acc, tail.clone()))
+        })
     }
 
     fn eval_filter(&mut self, expr: SExp) -> Result {
-        let (predicate, tail) = expr.split_car()?;
-
-        self.eval(tail.car()?)?
-            .into_iter()
-            .filter_map(
-                |e| match self.eval(Null.cons(e.clone()).cons(predicate.to_owned())) {
-                    Ok(Atom(Boolean(false))) => None,
-                    Ok(_) => Some(Ok(e)),
-                    err => Some(err),
-                },
-            )
-            .collect()
+        self.eval_list(expr, |c, args| {
+            let (predicate, rest) = args.split_car()?;
+            let list = rest.car()?;
+            list.len()?;
+            Ok(c.filter_from(predicate, list, Null))
+        })
     }
 
+    fn filter_from(&mut self, predicate: SExp, rest: SExp, done: SExp) -> SExp {
+        let (head, tail) = match rest {
+            Pair(p) => p.into_parts(),
+            _ => return reverse(done),
+        };
+
+        let call = application(predicate.clone(), Null.cons(head.clone()));
+        self.eval_then(call, move |c, keep| {
+            let done = match keep {
+                Atom(Boolean(false)) => done.clone(),
+                _ => done.clone().cons(head.clone()),
+            };
+            Ok(c.filter_from(predicate.clone(), tail.clone(), done))
+        })
+    }
+
+    #[allow(clippy::too_many_lines)]
     fn num_base(&mut self) {
         define!(
             self,
@@ -399,10 +417,45 @@ impl Context {
         define_with!(self, "add1", |e| e + Num::Int(1), make_unary_numeric);
         define_with!(self, "sub1", |e| e - Num::Int(1), make_unary_numeric);
 
-        define_with!(self, "=", |l, r| l == r, make_binary_numeric);
+        define_with!(self, "positive?", |n| n > Num::Int(0), make_unary_numeric);
+        define_with!(self, "negative?", |n| n < Num::Int(0), make_unary_numeric);
+        define_with!(
+            self,
+            "odd?",
+            |n| n.modulo(Num::Int(2)) == Num::Int(1),
+            make_unary_numeric
+        );
+        define_with!(
+            self,
+            "even?",
+            |n| n.modulo(Num::Int(2)) == Num::Int(0),
+            make_unary_numeric
+        );
 
-        define_with!(self, "<", |l, r| l < r, make_binary_numeric);
-        define_with!(self, ">", |l, r| l > r, make_binary_numeric);
+        define!(self, "number?", |e| is_number(e, |_| true), 1);
+        define!(self, "complex?", |e| is_number(e, |_| true), 1);
+        define!(self, "real?", |e| is_number(e, Num::is_real), 1);
+        define!(self, "rational?", |e| is_number(e, Num::is_rational), 1);
+        define!(self, "integer?", |e| is_number(e, Num::is_integer), 1);
+        define!(
+            self,
+            "exact-integer?",
+            |e| is_number(e, |n| n.is_exact() && n.is_integer()),
+            1
+        );
+        define_with!(self, "exact?", |n| n.is_exact(), make_unary_numeric);
+        define_with!(self, "inexact?", |n| !n.is_exact(), make_unary_numeric);
+        define_with!(self, "nan?", Num::is_nan, make_unary_numeric);
+        define_with!(self, "finite?", Num::is_finite, make_unary_numeric);
+        define_with!(self, "infinite?", Num::is_infinite, make_unary_numeric);
+
+        define!(self, "=", |e| compare(e, |l, r| l == r), (1,));
+        define!(self, "<", |e| compare(reals(e)?, |l, r| l < r), (1,));
+        define!(self, ">", |e| compare(reals(e)?, |l, r| l > r), (1,));
+        define!(self, "<=", |e| compare(reals(e)?, |l, r| l <= r), (1,));
+        define!(self, ">=", |e| compare(reals(e)?, |l, r| l >= r), (1,));
+        define!(self, "max", |e| extremum(e, |l, r| l > r), (1,));
+        define!(self, "min", |e| extremum(e, |l, r| l < r), (1,));
         define_with!(self, "abs", Num::abs, make_unary_numeric);
 
         self.lang.insert(
@@ -410,18 +463,355 @@ impl Context {
             make_fold_numeric(Num::Int(0), std::ops::Add::add, Some("+")),
         );
 
-        define_with!(self, "-", std::ops::Sub::sub, make_fold_from0_numeric);
+        define!(
+            self,
+            "-",
+            |e| fold_or_unary(e, std::ops::Neg::neg, std::ops::Sub::sub),
+            (1,)
+        );
 
         self.lang.insert(
             "*".to_string(),
             make_fold_numeric(Num::Int(1), std::ops::Mul::mul, Some("*")),
         );
 
-        define_with!(self, "/", std::ops::Div::div, make_fold_from0_numeric);
-        define_with!(self, "remainder", std::ops::Rem::rem, make_binary_numeric);
-        define_with!(self, "pow", Num::pow, make_binary_numeric);
+        define!(
+            self,
+            "/",
+            |e| {
+                // with one argument, that argument is the divisor
+                let skip = usize::from(e.len()? > 1);
+                if e.iter().skip(skip).any(|d| is_exact_zero(&d)) {
+                    return Err(Error::DivideByZero { op: "/" });
+                }
+                fold_or_unary(e, Num::recip, std::ops::Div::div)
+            },
+            (1,)
+        );
+        define!(self, "pow", expt, 2);
+        define!(self, "expt", expt, 2);
+        define_with!(self, "square", |n| n.clone() * n, make_unary_numeric);
+        define_with!(self, "sqrt", Num::sqrt, make_unary_numeric);
+
+        define!(
+            self,
+            "quotient",
+            |e| divide(e, "quotient", Num::quotient),
+            2
+        );
+        define!(
+            self,
+            "remainder",
+            |e| divide(e, "remainder", std::ops::Rem::rem),
+            2
+        );
+        define!(self, "modulo", |e| divide(e, "modulo", Num::modulo), 2);
+        define!(
+            self,
+            "truncate-quotient",
+            |e| divide(e, "truncate-quotient", Num::quotient),
+            2
+        );
+        define!(
+            self,
+            "truncate-remainder",
+            |e| divide(e, "truncate-remainder", std::ops::Rem::rem),
+            2
+        );
+        define!(
+            self,
+            "floor-quotient",
+            |e| divide(e, "floor-quotient", |n, d| (n.clone()
+                - n.modulo(d.clone()))
+                / d),
+            2
+        );
+        define!(
+            self,
+            "floor-remainder",
+            |e| divide(e, "floor-remainder", Num::modulo),
+            2
+        );
+        define!(
+            self,
+            "gcd",
+            |e| integer_fold(e, Num::Int(0), Num::gcd),
+            (0,)
+        );
+        define!(
+            self,
+            "lcm",
+            |e| integer_fold(e, Num::Int(1), Num::lcm),
+            (0,)
+        );
+
+        define_with!(self, "floor", Num::floor, make_unary_numeric);
+        define_with!(self, "ceiling", Num::ceil, make_unary_numeric);
+        define_with!(self, "round", Num::round, make_unary_numeric);
+        define_with!(self, "truncate", Num::trunc, make_unary_numeric);
+
+        define_with!(
+            self,
+            "make-rectangular",
+            Num::make_rectangular,
+            make_binary_numeric
+        );
+        define_with!(self, "make-polar", Num::make_polar, make_binary_numeric);
+        define_with!(self, "real-part", Num::real_part, make_unary_numeric);
+        define_with!(self, "imag-part", Num::imag_part, make_unary_numeric);
+        define_with!(self, "magnitude", Num::magnitude, make_unary_numeric);
+        define_with!(self, "angle", Num::angle, make_unary_numeric);
+
+        define_with!(self, "numerator", Num::numerator, make_unary_numeric);
+        define_with!(self, "denominator", Num::denominator, make_unary_numeric);
+        define_with!(self, "exact->inexact", Num::to_inexact, make_unary_numeric);
+        define_with!(self, "inexact", Num::to_inexact, make_unary_numeric);
+        define_with!(self, "inexact->exact", to_exact, make_unary_expr);
+        define_with!(self, "exact", to_exact, make_unary_expr);
+
+        define!(self, "number->string", number_to_string, (1, 2));
+        define!(self, "string->number", string_to_number, (1, 2));
 
         self.lang
             .insert("pi".to_string(), std::f64::consts::PI.into());
     }
 }
+
+/// Collect the arguments of a variadic numeric procedure.
+fn numbers(args: SExp) -> std::result::Result<Vec<Num>, Error> {
+    args.into_iter()
+        .map(|e| match e {
+            Atom(Number(n)) => Ok(n),
+            other => Err(Error::Type {
+                expected: "number",
+                given: other.type_of().to_string(),
+            }),
+        })
+        .collect()
+}
+
+/// Check that the arguments are all real, since only those are ordered.
+fn reals(args: SExp) -> Result {
+    match args
+        .iter()
+        .find(|e| !matches!(e, Atom(Number(n)) if n.is_real()))
+    {
+        Some(e) => Err(Error::Type {
+            expected: "real number",
+            given: e.to_string(),
+        }),
+        None => Ok(args),
+    }
+}
+
+fn integers(args: SExp) -> std::result::Result<Vec<Num>, Error> {
+    let nums = numbers(args)?;
+
+    match nums.iter().find(|n| !n.is_integer()) {
+        Some(n) => Err(Error::Type {
+            expected: "integer",
+            given: n.to_string(),
+        }),
+        None => Ok(nums),
+    }
+}
+
+/// Convert an index into a string or vector, which must be an exact,
+/// non-negative integer.
+fn index(e: SExp) -> std::result::Result<usize, Error> {
+    match e {
+        Atom(Number(n)) if n.is_exact() && n.is_integer() && !n.clone().is_sign_negative() => {
+            Ok(n.into())
+        }
+        _ => Err(Error::Type {
+            expected: "index",
+            given: e.to_string(),
+        }),
+    }
+}
+
+fn is_number(args: SExp, pred: impl Fn(&Num) -> bool) -> Result {
+    Ok(matches!(args.car()?, Atom(Number(n)) if pred(&n)).into())
+}
+
+/// Check that each adjacent pair of arguments is in order.
+fn compare(args: SExp, in_order: impl Fn(&Num, &Num) -> bool) -> Result {
+    Ok(numbers(args)?
+        .windows(2)
+        .all(|pair| in_order(&pair[0], &pair[1]))
+        .into())
+}
+
+fn extremum(args: SExp, better: impl Fn(&Num, &Num) -> bool) -> Result {
+    let nums = numbers(reals(args)?)?;
+    // NaN is unordered, so it can't be beaten and always comes out
+    if let Some(nan) = nums.iter().find(|&n| n.clone().is_nan()) {
+        return Ok(nan.clone().into());
+    }
+    let inexact = nums.iter().any(|n| !n.is_exact());
+    let best = nums
+        .into_iter()
+        .reduce(|best, n| if better(&n, &best) { n } else { best })
+        .ok_or(Error::ArityMin {
+            expected: 1,
+            given: 0,
+        })?;
+
+    Ok(if inexact { best.to_inexact() } else { best }.into())
+}
+
+/// Fold the arguments, or apply `unary` if there is only one (like `(- 3)`).
+fn fold_or_unary(args: SExp, unary: fn(Num) -> Num, f: fn(Num, Num) -> Num) -> Result {
+    let mut nums = numbers(args)?.into_iter();
+    let first = nums.next().ok_or(Error::ArityMin {
+        expected: 1,
+        given: 0,
+    })?;
+
+    Ok(match nums.next() {
+        Some(second) => nums.fold(f(first, second), f),
+        None => unary(first),
+    }
+    .into())
+}
+
+fn expt(args: SExp) -> Result {
+    let mut nums = numbers(args)?.into_iter();
+
+    match (nums.next(), nums.next()) {
+        (Some(base), Some(exp)) if base.is_exact() && exp.is_exact() && exp.is_integer() => {
+            let unit = [-1, 0, 1].iter().any(|&u| base == Num::Int(u));
+            if base == Num::Int(0) && exp.clone().is_sign_negative() {
+                Err(Error::DivideByZero { op: "expt" })
+            } else if !unit && exp.exact_exponent().is_none() {
+                // the result would have billions of digits
+                Err(Error::Type {
+                    expected: "exponent of at most 32 bits",
+                    given: exp.to_string(),
+                })
+            } else {
+                Ok(base.pow(exp).into())
+            }
+        }
+        (Some(base), Some(exp)) => Ok(base.pow(exp).into()),
+        _ => Err(Error::Arity {
+            expected: 2,
+            given: 1,
+        }),
+    }
+}
+
+fn is_exact_zero(e: &SExp) -> bool {
+    matches!(e, Atom(Number(n)) if n.is_exact() && *n == Num::Int(0))
+}
+
+fn divide(args: SExp, op: &'static str, f: fn(Num, Num) -> Num) -> Result {
+    let mut nums = integers(args)?.into_iter();
+
+    match (nums.next(), nums.next()) {
+        (Some(_), Some(d)) if d.is_exact() && d == Num::Int(0) => Err(Error::DivideByZero { op }),
+        (Some(n), Some(d)) => Ok(f(n, d).into()),
+        _ => Err(Error::Arity {
+            expected: 2,
+            given: 1,
+        }),
+    }
+}
+
+fn integer_fold(args: SExp, init: Num, f: fn(Num, Num) -> Num) -> Result {
+    Ok(integers(args)?.into_iter().fold(init, f).into())
+}
+
+fn to_exact(arg: SExp) -> Result {
+    match arg {
+        Atom(Number(n)) if !n.is_real() => Err(Error::Type {
+            expected: "real number (complex numbers are always inexact)",
+            given: n.to_string(),
+        }),
+        Atom(Number(n)) => n.to_exact().map(SExp::from).ok_or(Error::Type {
+            expected: "finite real number",
+            given: n.to_string(),
+        }),
+        other => Err(Error::Type {
+            expected: "number",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
+
+fn radix(args: SExp) -> std::result::Result<u32, Error> {
+    match args.cdr()? {
+        Null => Ok(10),
+        rest => {
+            let given = rest.car()?;
+            [2, 8, 10, 16]
+                .iter()
+                .copied()
+                .find(|&r: &u32| given == SExp::from(r as usize))
+                .ok_or(Error::Type {
+                    expected: "radix",
+                    given: given.to_string(),
+                })
+        }
+    }
+}
+
+fn number_to_string(args: SExp) -> Result {
+    let radix = radix(args.clone())?;
+
+    match args.car()? {
+        Atom(Number(n)) => n
+            .to_string_radix(radix)
+            .map(|s| Atom(LispString(s.into())))
+            .ok_or(Error::Type {
+                expected: "exact number",
+                given: n.to_string(),
+            }),
+        other => Err(Error::Type {
+            expected: "number",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
+
+fn string_to_number(args: SExp) -> Result {
+    let mut radix = radix(args.clone())?;
+
+    match args.car()? {
+        Atom(LispString(s)) => {
+            let s = s.borrow();
+            let mut s = s.as_str();
+            for (prefix, r) in &[("#b", 2), ("#o", 8), ("#d", 10), ("#x", 16)] {
+                if let Some(rest) = s.strip_prefix(prefix) {
+                    s = rest;
+                    radix = *r;
+                }
+            }
+
+            let num = if radix == 10 {
+                s.parse::<Num>().ok()
+            } else {
+                Num::from_str_radix(s, radix)
+            };
+
+            Ok(num.map_or(Atom(Boolean(false)), SExp::from))
+        }
+        other => Err(Error::Type {
+            expected: "string",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
+
+/// A list in the opposite order.
+fn reverse(list: SExp) -> SExp {
+    list.into_iter().fold(Null, SExp::cons)
+}
+
+fn parameter(init: SExp, converter: Option<SExp>) -> SExp {
+    SExp::from(Proc::new::<_, _, &str>(
+        Func::Parameter(Rc::new(Parameter { init, converter })),
+        0,
+        None,
+    ))
+}
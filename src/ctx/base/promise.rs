@@ -0,0 +1,199 @@
+use super::super::super::primitives::{Promise, PromiseState};
+use super::super::super::proc::utils::make_unary_expr;
+use super::super::super::Primitive::{self, Number};
+use super::super::super::SExp::{self, Atom, Null, Pair};
+use super::super::super::{Error, Result};
+use super::super::Context;
+
+macro_rules! define_with {
+    ( $ctx:ident, $name:expr, $proc:expr, $tform:expr ) => {
+        $ctx.lang
+            .insert($name.to_string(), $tform($proc, Some($name)))
+    };
+}
+
+macro_rules! define_ctx {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Ctx(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+fn count(e: SExp) -> std::result::Result<usize, Error> {
+    match e {
+        Atom(Number(n)) => Ok(n.into()),
+        _ => Err(Error::Type {
+            expected: "number",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+impl Context {
+    /// Force a promise, deferring the evaluation of its body if it has not
+    /// been forced yet. Anything else is its own value.
+    fn force(&mut self, obj: SExp) -> SExp {
+        let promise = match obj {
+            Atom(Primitive::Promise(p)) => p,
+            other => return other,
+        };
+
+        match promise.state() {
+            PromiseState::Done(val) => val,
+            PromiseState::Pending { body, envt, lazy } => {
+                self.push_then(move |c, val| c.settle(&promise, val, lazy));
+                self.use_env(envt);
+                self.defer(body)
+            }
+        }
+    }
+
+    /// Record the value of a promise's body, or, for a lazy body, replace
+    /// the promise with the one it returned and keep forcing.
+    fn settle(&mut self, promise: &Promise, val: SExp, lazy: bool) -> Result {
+        // the body may have forced this promise itself
+        if let PromiseState::Done(val) = promise.state() {
+            return Ok(val);
+        }
+
+        if !lazy {
+            promise.set_state(PromiseState::Done(val.clone()));
+            return Ok(val);
+        }
+
+        match val {
+            Atom(Primitive::Promise(next)) => {
+                promise.set_state(next.state());
+                next.share(promise);
+                Ok(self.force(Atom(Primitive::Promise(promise.clone()))))
+            }
+            other => Err(Error::Type {
+                expected: "promise",
+                given: other.type_of().to_string(),
+            }),
+        }
+    }
+
+    /// The first `n` elements of a stream, in a list.
+    fn stream_head(&mut self, stream: SExp, n: usize, mut done: Vec<SExp>) -> Result {
+        if n == 0 {
+            return Ok(done.into());
+        }
+
+        let (head, tail) = stream_pair(stream)?;
+        done.push(head);
+        if n == 1 {
+            return Ok(done.into());
+        }
+
+        self.push_then(move |c, rest| c.stream_head(rest, n - 1, done.clone()));
+        Ok(self.force(tail))
+    }
+
+    /// The stream left after dropping the first `n` elements.
+    fn stream_tail(&mut self, stream: SExp, n: usize) -> Result {
+        if n == 0 {
+            return Ok(stream);
+        }
+
+        let (_, tail) = stream_pair(stream)?;
+        self.push_then(move |c, rest| c.stream_tail(rest, n - 1));
+        Ok(self.force(tail))
+    }
+
+    pub(super) fn promise(&mut self) {
+        define_ctx!(
+            self,
+            "force",
+            |c, e| Ok(c.eval_then(e.car()?, |c, p| Ok(c.force(p)))),
+            1
+        );
+        define_with!(
+            self,
+            "make-promise",
+            |e| match e {
+                p @ Atom(Primitive::Promise(_)) => Ok(p),
+                val => Ok(Atom(Primitive::Promise(Promise::new(PromiseState::Done(
+                    val
+                ))))),
+            },
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "promise?",
+            |e| Ok(matches!(e, Atom(Primitive::Promise(_))).into()),
+            make_unary_expr
+        );
+
+        // streams
+        self.lang.insert("the-empty-stream".to_string(), Null);
+        self.lang.insert("stream-nil".to_string(), Null);
+        define_with!(
+            self,
+            "stream-pair?",
+            |e| Ok(stream_pair(e).is_ok().into()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "stream-null?",
+            |e| Ok(e.is_empty().into()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "empty-stream?",
+            |e| Ok(e.is_empty().into()),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "stream-car",
+            |e| Ok(stream_pair(e)?.0),
+            make_unary_expr
+        );
+        define_ctx!(
+            self,
+            "stream-cdr",
+            |c, e| Ok(c.eval_then(e.car()?, |c, s| Ok(c.force(stream_pair(s)?.1)))),
+            1
+        );
+        define_ctx!(
+            self,
+            "stream-head",
+            |c, e| c.eval_list(e, |c, args| {
+                let (stream, rest) = args.split_car()?;
+                c.stream_head(stream, count(rest.car()?)?, Vec::new())
+            }),
+            2
+        );
+        define_ctx!(
+            self,
+            "stream-tail",
+            |c, e| c.eval_list(e, |c, args| {
+                let (stream, rest) = args.split_car()?;
+                c.stream_tail(stream, count(rest.car()?)?)
+            }),
+            2
+        );
+    }
+}
+
+/// The parts of a stream pair: its first element and the promise of the
+/// rest.
+fn stream_pair(e: SExp) -> std::result::Result<(SExp, SExp), Error> {
+    match e {
+        Pair(p) if matches!(*p.tail(), Atom(Primitive::Promise(_))) => Ok(p.into_parts()),
+        other => Err(Error::Type {
+            expected: "stream pair",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
@@ -0,0 +1,403 @@
+use std::cmp::Ordering;
+
+use super::super::super::proc::utils::{make_binary_expr, make_unary_expr};
+use super::super::super::Error;
+use super::super::super::Primitive::{Boolean, Character, String as LispString, Undefined};
+use super::super::super::SExp::{self, Atom, Null};
+use super::super::Context;
+use super::index;
+
+macro_rules! define_with {
+    ( $ctx:ident, $name:expr, $proc:expr, $tform:expr ) => {
+        $ctx.lang
+            .insert($name.to_string(), $tform($proc, Some($name)))
+    };
+}
+
+macro_rules! define {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Pure(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+macro_rules! define_ctx {
+    ( $ctx:ident, $name:expr, $proc:expr, $arity:expr ) => {
+        $ctx.lang.insert(
+            $name.to_string(),
+            $crate::SExp::from($crate::Proc::new(
+                $crate::Func::Ctx(::std::rc::Rc::new($proc)),
+                $arity,
+                Some($name),
+            )),
+        )
+    };
+}
+
+fn string(e: SExp) -> Result<String, Error> {
+    match e {
+        Atom(LispString(s)) => Ok(s.borrow().clone()),
+        _ => Err(Error::Type {
+            expected: "string",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn character(e: SExp) -> Result<char, Error> {
+    match e {
+        Atom(Character(c)) => Ok(c),
+        _ => Err(Error::Type {
+            expected: "char",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+fn found(i: Option<usize>) -> SExp {
+    i.map_or(Atom(Boolean(false)), SExp::from)
+}
+
+/// The characters of a string between the optional start and end indices
+/// that follow it in the argument list.
+fn chars_between(args: SExp) -> Result<Vec<char>, Error> {
+    let (s, rest) = args.split_car()?;
+    let mut chars = string(s)?.chars().collect::<Vec<_>>();
+    let mut bounds = rest.into_iter().map(index);
+
+    let start = bounds.next().transpose()?.unwrap_or(0);
+    let end = bounds.next().transpose()?.unwrap_or(chars.len());
+    if end > chars.len() {
+        return Err(Error::Index { i: end });
+    }
+    if start > end {
+        return Err(Error::Index { i: start });
+    }
+
+    chars.truncate(end);
+    Ok(chars.split_off(start))
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+fn is_string(e: SExp) -> Result<SExp, Error> {
+    Ok(matches!(e, Atom(LispString(_))).into())
+}
+
+fn make_string(args: SExp) -> Result<SExp, Error> {
+    let (k, rest) = args.split_car()?;
+    let fill = match rest {
+        Null => ' ',
+        _ => character(rest.car()?)?,
+    };
+
+    Ok(SExp::from(
+        std::iter::repeat_n(fill, index(k)?).collect::<String>(),
+    ))
+}
+
+fn string_from_chars(args: SExp) -> Result<SExp, Error> {
+    Ok(SExp::from(
+        args.into_iter()
+            .map(character)
+            .collect::<Result<String, _>>()?,
+    ))
+}
+
+fn string_length(s: SExp) -> Result<SExp, Error> {
+    Ok(string(s)?.chars().count().into())
+}
+
+fn string_ref(s: SExp, k: SExp) -> Result<SExp, Error> {
+    let k = index(k)?;
+
+    string(s)?
+        .chars()
+        .nth(k)
+        .map(SExp::from)
+        .ok_or(Error::Index { i: k })
+}
+
+fn substring(args: SExp) -> Result<SExp, Error> {
+    Ok(SExp::from(
+        chars_between(args)?.into_iter().collect::<String>(),
+    ))
+}
+
+fn string_append(args: SExp) -> Result<SExp, Error> {
+    Ok(SExp::from(
+        args.into_iter()
+            .map(string)
+            .collect::<Result<String, _>>()?,
+    ))
+}
+
+fn string_to_list(args: SExp) -> Result<SExp, Error> {
+    Ok(chars_between(args)?.into_iter().map(SExp::from).collect())
+}
+
+fn compare(args: SExp, fold_case: bool, in_order: fn(Ordering) -> bool) -> Result<SExp, Error> {
+    let strings = args
+        .into_iter()
+        .map(|s| {
+            let s = string(s)?;
+            Ok(if fold_case { s.to_lowercase() } else { s })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(strings
+        .windows(2)
+        .all(|pair| in_order(pair[0].cmp(&pair[1])))
+        .into())
+}
+
+fn string_search_forward(args: SExp) -> Result<SExp, Error> {
+    let (pattern, rest) = args.split_car()?;
+    let (s, rest) = rest.split_car()?;
+    let (pattern, s) = (string(pattern)?, string(s)?);
+    let start = match rest {
+        Null => 0,
+        _ => index(rest.car()?)?,
+    };
+
+    let offset = s
+        .char_indices()
+        .nth(start)
+        .map(|(i, _)| i)
+        .or(if start == s.chars().count() {
+            Some(s.len())
+        } else {
+            None
+        })
+        .ok_or(Error::Index { i: start })?;
+
+    Ok(found(
+        s[offset..]
+            .find(&pattern)
+            .map(|i| start + s[offset..offset + i].chars().count()),
+    ))
+}
+
+fn string_contains(s: SExp, pattern: SExp) -> Result<SExp, Error> {
+    let (s, pattern) = (string(s)?, string(pattern)?);
+    Ok(found(s.find(&pattern).map(|i| s[..i].chars().count())))
+}
+
+fn string_index(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let (s, rest) = args.split_car()?;
+    let s = string(ctx.eval(s)?)?;
+    let pred = ctx.eval(rest.car()?)?;
+
+    for (i, c) in s.chars().enumerate() {
+        let matches = match &pred {
+            Atom(Character(p)) => *p == c,
+            _ => ctx.call(pred.clone(), Null.cons(SExp::from(c)))? != Atom(Boolean(false)),
+        };
+
+        if matches {
+            return Ok(i.into());
+        }
+    }
+
+    Ok(found(None))
+}
+
+fn string_split(s: SExp, delimiter: SExp) -> Result<SExp, Error> {
+    let (s, delimiter) = (string(s)?, character(delimiter)?);
+    Ok(s.split(delimiter).map(SExp::from).collect())
+}
+
+fn string_join(args: SExp) -> Result<SExp, Error> {
+    let (list, rest) = args.split_car()?;
+    list.len()?;
+    let delimiter = match rest {
+        Null => " ".to_string(),
+        _ => string(rest.car()?)?,
+    };
+
+    Ok(SExp::from(
+        list.into_iter()
+            .map(string)
+            .collect::<Result<Vec<_>, _>>()?
+            .join(&delimiter),
+    ))
+}
+
+/// Pad or truncate a string to `n` characters, keeping its right end if
+/// `left` is set and its left end otherwise.
+fn string_pad(args: SExp, left: bool) -> Result<SExp, Error> {
+    let (s, rest) = args.split_car()?;
+    let (n, rest) = rest.split_car()?;
+    let (chars, n) = (string(s)?.chars().collect::<Vec<_>>(), index(n)?);
+    let fill = match rest {
+        Null => ' ',
+        _ => character(rest.car()?)?,
+    };
+
+    let padding = std::iter::repeat_n(fill, n.saturating_sub(chars.len()));
+    let padded: String = if left {
+        padding
+            .chain(chars[chars.len().saturating_sub(n)..].iter().copied())
+            .collect()
+    } else {
+        chars[..n.min(chars.len())]
+            .iter()
+            .copied()
+            .chain(padding)
+            .collect()
+    };
+
+    Ok(SExp::from(padded))
+}
+
+/// Replace the characters of a string in place, so the change is seen
+/// through every reference to it.
+fn mutate(s: SExp, f: impl FnOnce(&mut Vec<char>) -> Result<(), Error>) -> Result<SExp, Error> {
+    match s {
+        Atom(LispString(s)) => {
+            let mut chars = s.borrow().chars().collect();
+            f(&mut chars)?;
+            *s.borrow_mut() = chars.into_iter().collect();
+            Ok(Atom(Undefined))
+        }
+        e => Err(Error::Type {
+            expected: "string",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+fn string_set(args: SExp) -> Result<SExp, Error> {
+    let (s, tail) = args.split_car()?;
+    let (k, tail) = tail.split_car()?;
+    let k = index(k)?;
+    let c = character(tail.car()?)?;
+
+    mutate(s, |chars| {
+        *chars.get_mut(k).ok_or(Error::Index { i: k })? = c;
+        Ok(())
+    })
+}
+
+fn string_fill(args: SExp) -> Result<SExp, Error> {
+    let (s, tail) = args.split_car()?;
+    let (c, tail) = tail.split_car()?;
+    let c = character(c)?;
+    let bounds = tail.into_iter().map(index).collect::<Result<Vec<_>, _>>()?;
+
+    mutate(s, |chars| {
+        let start = bounds.first().copied().unwrap_or(0);
+        let end = bounds.get(1).copied().unwrap_or(chars.len());
+        chars
+            .get_mut(start..end)
+            .ok_or(Error::Index { i: end })?
+            .iter_mut()
+            .for_each(|ch| *ch = c);
+        Ok(())
+    })
+}
+
+impl Context {
+    pub(super) fn string(&mut self) {
+        define_with!(self, "string?", is_string, make_unary_expr);
+        define!(self, "make-string", make_string, (1, 2));
+        define!(self, "string", string_from_chars, (0,));
+        define_with!(self, "string-length", string_length, make_unary_expr);
+        define_with!(self, "string-ref", string_ref, make_binary_expr);
+        define!(self, "substring", substring, (2, 3));
+        define!(self, "string-copy", substring, (1, 3));
+        define!(self, "string-append", string_append, (0,));
+        define!(self, "string->list", string_to_list, (1, 3));
+
+        define_with!(
+            self,
+            "string-upcase",
+            |s| Ok(SExp::from(string(s)?.to_uppercase())),
+            make_unary_expr
+        );
+        define_with!(
+            self,
+            "string-downcase",
+            |s| Ok(SExp::from(string(s)?.to_lowercase())),
+            make_unary_expr
+        );
+
+        define!(
+            self,
+            "string=?",
+            |e| compare(e, false, Ordering::is_eq),
+            (1,)
+        );
+        define!(
+            self,
+            "string<?",
+            |e| compare(e, false, Ordering::is_lt),
+            (1,)
+        );
+        define!(
+            self,
+            "string>?",
+            |e| compare(e, false, Ordering::is_gt),
+            (1,)
+        );
+        define!(
+            self,
+            "string<=?",
+            |e| compare(e, false, Ordering::is_le),
+            (1,)
+        );
+        define!(
+            self,
+            "string>=?",
+            |e| compare(e, false, Ordering::is_ge),
+            (1,)
+        );
+        define!(
+            self,
+            "string-ci=?",
+            |e| compare(e, true, Ordering::is_eq),
+            (1,)
+        );
+        define!(
+            self,
+            "string-ci<?",
+            |e| compare(e, true, Ordering::is_lt),
+            (1,)
+        );
+        define!(
+            self,
+            "string-ci>?",
+            |e| compare(e, true, Ordering::is_gt),
+            (1,)
+        );
+        define!(
+            self,
+            "string-ci<=?",
+            |e| compare(e, true, Ordering::is_le),
+            (1,)
+        );
+        define!(
+            self,
+            "string-ci>=?",
+            |e| compare(e, true, Ordering::is_ge),
+            (1,)
+        );
+
+        define_ctx!(self, "string-index", string_index, 2);
+        define!(self, "string-search-forward", string_search_forward, (2, 3));
+        define_with!(self, "string-contains", string_contains, make_binary_expr);
+        define_with!(self, "string-split", string_split, make_binary_expr);
+        define!(self, "string-join", string_join, (1, 2));
+        define!(self, "string-pad-left", |e| string_pad(e, true), (2, 3));
+        define!(self, "string-pad-right", |e| string_pad(e, false), (2, 3));
+
+        define!(self, "string-set!", string_set, 3);
+        define!(self, "string-fill!", string_fill, (2, 4));
+    }
+}
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
 use super::*;
+use crate::Cons;
 
 fn eval(e: SExp) -> Result {
     Context::base().eval(e)
@@ -18,17 +19,23 @@ fn eq_test() {
 
     assert_eq!(
         eval(sexp![eq(), "woohoo", "woohoo"]).unwrap(),
+        SExp::from(false)
+    );
+
+    assert_eq!(
+        eval(sexp![eq(), 1 + 2 + 3, 1 + 5]).unwrap(),
         SExp::from(true)
     );
 
+    // exactness has to match, like `eqv?`
     assert_eq!(
         eval(sexp![eq(), 1 + 2 + 3, 9. - 3.5 + 0.25 * 2.]).unwrap(),
-        SExp::from(true)
+        SExp::from(false)
     );
 
     assert_eq!(
         eval(sexp![eq(), sexp![list(), 1, 2], sexp![list(), 1, 2]]).unwrap(),
-        SExp::from(true)
+        SExp::from(false)
     );
 
     assert_eq!(
@@ -93,13 +100,7 @@ fn cons() {
     let item_3 = || SExp::sym("null");
 
     // sanity check
-    assert_eq!(
-        SExp::from((item_1(),)),
-        Pair {
-            head: Box::new(item_1()),
-            tail: Box::new(Null)
-        }
-    );
+    assert_eq!(SExp::from((item_1(),)), Pair(Cons::new(item_1(), Null)));
 
     assert_eq!(
         eval(sexp![cons(), item_1(), item_3()]).unwrap(),
@@ -185,3 +186,111 @@ fn type_of() {
         eval(sexp![tpf(), sexp![SExp::sym("list"), false, '\0']]).unwrap(),
     );
 }
+
+#[test]
+fn write_round_trip() {
+    let mut ctx = Context::base().capturing();
+    ctx.run(r#"(write "tab\there \"quoted\" back\\slash\nnew\x7;")"#)
+        .unwrap();
+    let written = ctx.get_output().unwrap();
+    assert_eq!(written, r#""tab\there \"quoted\" back\\slash\nnew\x7;""#);
+
+    assert_eq!(
+        written.parse::<SExp>().unwrap(),
+        SExp::from("tab\there \"quoted\" back\\slash\nnew\u{7}")
+    );
+
+    ctx.capture();
+    ctx.run(r#"(display "a\nb")"#).unwrap();
+    assert_eq!(ctx.get_output().unwrap(), "a\nb");
+}
+
+#[test]
+fn pair_identity() {
+    let mut ctx = Context::base();
+    ctx.run("(define a (list 1 (list 2 3)))").unwrap();
+    ctx.run("(define b a)").unwrap();
+    ctx.run("(define inner (car (cdr a)))").unwrap();
+
+    ctx.run("(set-car! b 9)").unwrap();
+    ctx.run("(set-car! inner 'x)").unwrap();
+    assert_eq!(ctx.run("a").unwrap(), ctx.run("'(9 (x 3))").unwrap());
+    assert_eq!(ctx.run("(eq? a b)").unwrap(), SExp::from(true));
+
+    assert!(ctx.run("(set-car! '() 1)").is_err());
+}
+
+#[test]
+fn circular_list() {
+    let mut ctx = Context::base().capturing();
+    ctx.run("(define c (list 1 2 3))").unwrap();
+    ctx.run("(set-cdr! (cdr (cdr c)) c)").unwrap();
+
+    assert_eq!(ctx.run("(car (cdr (cdr (cdr c))))").unwrap(), SExp::from(1));
+    assert!(ctx.run("c").unwrap().len().is_err());
+
+    ctx.run("(display c)").unwrap();
+    assert!(ctx.get_output().unwrap().ends_with("...)"));
+
+    // comparing cycles terminates, whether or not they line up
+    ctx.run("(define d (list 1 2 3))").unwrap();
+    ctx.run("(set-cdr! (cdr (cdr d)) d)").unwrap();
+    assert_eq!(ctx.run("(equal? c d)").unwrap(), SExp::from(true));
+    ctx.run("(set-car! d 0)").unwrap();
+    assert_eq!(ctx.run("(equal? c d)").unwrap(), SExp::from(false));
+
+    // as does writing a structure that contains itself
+    ctx.run("(define h (list 1 2))").unwrap();
+    ctx.run("(set-car! (cdr h) h)").unwrap();
+    ctx.run("(define v (make-vector 2 0))").unwrap();
+    ctx.run("(vector-set! v 1 v)").unwrap();
+    ctx.capture();
+    ctx.run("(write h)").unwrap();
+    ctx.run("(write v)").unwrap();
+    assert_eq!(ctx.get_output().unwrap(), "(1 ...)#(0 ...)");
+    assert_eq!(ctx.run("(equal? v v)").unwrap(), SExp::from(true));
+}
+
+#[test]
+fn long_list() {
+    let list = (0..1_000_000).map(SExp::from).collect::<SExp>();
+    let copy = (0..1_000_000).map(SExp::from).collect::<SExp>();
+
+    // neither comparing nor dropping recurses along the list
+    assert_eq!(list, copy);
+    drop(list);
+    drop(copy);
+}
+
+#[test]
+fn improper_arguments() {
+    let mut ctx = Context::base();
+    let list = ctx.get("list").unwrap();
+    let args = SExp::from(3).cons(SExp::from(2)).cons(SExp::from(1));
+
+    // the dotted tail isn't dropped from the arguments
+    assert!(ctx.call(list.clone(), args.clone()).is_err());
+    let mut iter = args.into_iter();
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.rest(), &SExp::from(3));
+
+    assert_eq!(
+        ctx.call(list, sexp![1, 2]).unwrap(),
+        ctx.run("'(1 2)").unwrap()
+    );
+}
+
+#[test]
+fn reenter_returned_builtin() {
+    let mut ctx = Context::base().capturing();
+    let program = "(define k #f)
+        (display (vector-map (lambda (x) (call/cc (lambda (c) (set! k c) x))) #(1 2 3)))
+        (define n 0)
+        (set! n (+ n 1))
+        (if (< n 2) (k 10) #f)
+        (display \"after\")";
+
+    // rather than ending the program early, re-entering `vector-map` fails
+    assert!(ctx.run(program).is_err());
+    assert_eq!(ctx.get_output().unwrap(), "#(1 2 3)");
+}
@@ -1,8 +1,10 @@
+use super::super::super::primitives::SharedVec;
 use super::super::super::proc::utils::{make_binary_expr, make_ternary_expr, make_unary_expr};
 use super::super::super::Error;
-use super::super::super::Primitive::{Number, Symbol, Undefined, Vector};
-use super::super::super::SExp::{self, Atom, Null};
-use super::super::Context;
+use super::super::super::Primitive::{Boolean, Number, Undefined, Vector};
+use super::super::super::SExp::{self, Atom, Null, Pair};
+use super::super::{single, Context};
+use super::index;
 
 macro_rules! define_with {
     ( $ctx:ident, $name:expr, $proc:expr, $tform:expr ) => {
@@ -37,6 +39,27 @@ macro_rules! define_ctx {
     };
 }
 
+fn vector(e: SExp) -> Result<SharedVec, Error> {
+    match e {
+        Atom(Vector(v)) => Ok(v),
+        _ => Err(Error::Type {
+            expected: "vector",
+            given: e.type_of().to_string(),
+        }),
+    }
+}
+
+/// Check that `start..end` is a valid range of a sequence of length `len`.
+fn bounds(start: usize, end: usize, len: usize) -> Result<(), Error> {
+    if end > len {
+        return Err(Error::Index { i: end });
+    }
+    if start > end {
+        return Err(Error::Index { i: start });
+    }
+    Ok(())
+}
+
 fn make_vector(exp: SExp) -> Result<SExp, Error> {
     let (first_arg, rest) = exp.split_car()?;
     let second_arg = match rest {
@@ -45,18 +68,12 @@ fn make_vector(exp: SExp) -> Result<SExp, Error> {
         _ => rest.car()?,
     };
 
-    match first_arg {
-        Atom(Number(n)) => Ok(Atom(Vector(vec![second_arg; n.into()]))),
-        _ => Err(Error::Type {
-            expected: "number",
-            given: first_arg.type_of().to_string(),
-        }),
-    }
+    Ok(Atom(Vector(vec![second_arg; index(first_arg)?].into())))
 }
 
 fn vector_copy(v: SExp) -> Result<SExp, Error> {
     match v {
-        vec @ Atom(Vector(_)) => Ok(vec),
+        Atom(Vector(vec)) => Ok(Atom(Vector(vec.to_vec().into()))),
         _ => Err(Error::Type {
             expected: "vector",
             given: v.type_of().to_string(),
@@ -83,60 +100,155 @@ fn vector_len(v: SExp) -> Result<SExp, Error> {
 }
 
 fn vector_ref(v: SExp, i: SExp) -> Result<SExp, Error> {
-    match (v, i) {
-        (Atom(Vector(vec)), Atom(Number(n))) => vec
-            .get(usize::from(n))
-            .map(ToOwned::to_owned)
-            .ok_or(Error::Index { i: n.into() }),
-        (Atom(Vector(_)), i) => Err(Error::Type {
-            expected: "number",
-            given: i.type_of().to_string(),
-        }),
-        (v, _) => Err(Error::Type {
-            expected: "vector",
-            given: v.type_of().to_string(),
-        }),
+    let vec = vector(v)?;
+    let i = index(i)?;
+
+    vec.get(i).ok_or(Error::Index { i })
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn vector_set(v: SExp, i: SExp, val: SExp) -> Result<SExp, Error> {
+    let vec = vector(v)?;
+    let i = index(i)?;
+
+    match vec.borrow_mut().get_mut(i) {
+        Some(elem) => *elem = val,
+        None => return Err(Error::Index { i }),
     }
+    Ok(Atom(Undefined))
 }
 
-fn vector_set(ctx: &mut Context, expr: SExp) -> Result<SExp, Error> {
-    let (s, tail) = expr.split_car()?;
-    let (num, tail) = tail.split_car()?;
-    let head = tail.car()?;
+/// `(vector-fill! vector fill [start [end]])`
+fn vector_fill(exp: SExp) -> Result<SExp, Error> {
+    let mut args = exp.into_iter();
+    let vec = vector(args.next().ok_or(Error::NullList)?)?;
+    let fill = args.next().ok_or(Error::NullList)?;
+    let len = vec.len();
+    let start = args.next().map_or(Ok(0), index)?;
+    let end = args.next().map_or(Ok(len), index)?;
 
-    let sym = match s {
-        Atom(Symbol(sym)) => sym,
-        e => {
-            return Err(Error::Type {
-                expected: "symbol",
-                given: e.type_of().to_string(),
-            });
+    bounds(start, end, len)?;
+
+    for elem in &mut vec.borrow_mut()[start..end] {
+        *elem = fill.clone();
+    }
+    Ok(Atom(Undefined))
+}
+
+/// A new vector of length `k` starting with the elements of `v`.
+fn vector_grow(v: SExp, k: SExp) -> Result<SExp, Error> {
+    let mut elements = vector(v)?.to_vec();
+    let k = index(k)?;
+    if k < elements.len() {
+        return Err(Error::Index { i: k });
+    }
+
+    elements.resize(k, Atom(Undefined));
+    Ok(Atom(Vector(elements.into())))
+}
+
+/// `(vector-binary-search vector key key-compare)`: find the element for
+/// which `(key-compare key element)` is zero in a sorted vector, or `#f`.
+fn vector_binary_search(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let mut args = ctx.eval_args(args)?.into_iter();
+    let vec = vector(args.next().ok_or(Error::NullList)?)?;
+    let key = args.next().ok_or(Error::NullList)?;
+    let compare = args.next().ok_or(Error::NullList)?;
+
+    let (mut lo, mut hi) = (0, vec.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        // the vector may have been changed by `key-compare`
+        let elem = vec.get(mid).ok_or(Error::Index { i: mid })?;
+
+        match ctx.call(compare.clone(), Null.cons(elem.clone()).cons(key.clone()))? {
+            Atom(Number(n)) if n == 0.into() => return Ok(elem),
+            Atom(Number(n)) if n < 0.into() => hi = mid,
+            Atom(Number(_)) => lo = mid + 1,
+            e => {
+                return Err(Error::Type {
+                    expected: "number",
+                    given: e.type_of().to_string(),
+                })
+            }
         }
-    };
-    let n = match ctx.eval(num)? {
-        Atom(Number(n)) => n,
-        e => {
-            return Err(Error::Type {
-                expected: "number",
-                given: e.type_of().to_string(),
-            });
+    }
+
+    Ok(Atom(Boolean(false)))
+}
+
+/// Stable merge sort of `items` by the Scheme predicate `less`.
+fn merge_sort(ctx: &mut Context, mut items: Vec<SExp>, less: &SExp) -> Result<Vec<SExp>, Error> {
+    if items.len() < 2 {
+        return Ok(items);
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let mut left = merge_sort(ctx, items, less)?.into_iter().peekable();
+    let mut right = merge_sort(ctx, right, less)?.into_iter().peekable();
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // only take from the right when it is strictly smaller, for stability
+        let args = Null.cons(l.clone()).cons(r.clone());
+        if ctx.call(less.clone(), args)? == Atom(Boolean(false)) {
+            merged.extend(left.next());
+        } else {
+            merged.extend(right.next());
         }
-    };
+    }
+    merged.extend(left);
+    merged.extend(right);
+
+    Ok(merged)
+}
 
-    match ctx.get(&sym) {
-        Some(Atom(Vector(mut vec))) => {
-            vec[usize::from(n)] = ctx.eval(head)?;
-            ctx.set(&sym, Atom(Vector(vec))).unwrap();
-            Ok(Atom(Undefined))
+/// `(sort sequence procedure)`: a sorted copy of a list or vector.
+fn sort(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let mut args = ctx.eval_args(args)?.into_iter();
+    let seq = args.next().ok_or(Error::NullList)?;
+    let less = args.next().ok_or(Error::NullList)?;
+
+    match seq {
+        Atom(Vector(vec)) => Ok(Atom(Vector(merge_sort(ctx, vec.to_vec(), &less)?.into()))),
+        list => {
+            list.len()?;
+            Ok(merge_sort(ctx, list.into_iter().collect(), &less)?
+                .into_iter()
+                .collect())
         }
-        Some(val) => Err(Error::Type {
-            expected: "vector",
-            given: val.type_of().to_string(),
-        }),
-        None => Err(Error::UndefinedSymbol { sym }),
     }
 }
 
+/// `(sort! sequence procedure)`: sort a list or vector in place.
+fn sort_in_place(ctx: &mut Context, args: SExp) -> Result<SExp, Error> {
+    let mut args = ctx.eval_args(args)?.into_iter();
+    let seq = args.next().ok_or(Error::NullList)?;
+    let less = args.next().ok_or(Error::NullList)?;
+
+    match seq {
+        Atom(Vector(vec)) => {
+            // sort a copy, so `less` can't see the vector half-sorted
+            let sorted = merge_sort(ctx, vec.to_vec(), &less)?;
+            *vec.borrow_mut() = sorted;
+        }
+        list => {
+            list.len()?;
+            let sorted = merge_sort(ctx, list.clone().into_iter().collect(), &less)?;
+
+            let mut rest = list;
+            for elem in sorted {
+                if let Pair(p) = rest {
+                    p.set_car(elem);
+                    rest = p.cdr();
+                }
+            }
+        }
+    }
+
+    Ok(Atom(Undefined))
+}
+
 fn vector_map(ctx: &mut Context, expr: SExp) -> Result<SExp, Error> {
     let (proc, tail) = expr.split_car()?;
 
@@ -151,80 +263,38 @@ fn vector_map(ctx: &mut Context, expr: SExp) -> Result<SExp, Error> {
     };
 
     let mut new_vec = Vec::new();
-    for expression in vec {
-        new_vec.push(ctx.eval(Null.cons(expression).cons(proc.clone()))?);
+    for expression in vec.to_vec() {
+        let val = ctx.eval(Null.cons(expression).cons(proc.clone()))?;
+        new_vec.push(single(val)?);
     }
-    Ok(Atom(Vector(new_vec)))
+    Ok(Atom(Vector(new_vec.into())))
 }
 
 fn subvector(v: SExp, start: SExp, end: SExp) -> Result<SExp, Error> {
-    match (v, start, end) {
-        (Atom(Vector(vec)), Atom(Number(n0)), Atom(Number(n1))) => {
-            let (i0, i1) = (n0.into(), n1.into());
-            if i0 >= vec.len() {
-                return Err(Error::Index { i: i0 });
-            }
-            if i1 >= vec.len() {
-                return Err(Error::Index { i: i1 });
-            }
+    let vec = vector(v)?;
+    let (start, end) = (index(start)?, index(end)?);
+    bounds(start, end, vec.len())?;
 
-            Ok(Atom(Vector(vec[i0..i1].to_vec())))
-        }
-        (Atom(Vector(_)), Atom(Number(_)), end) => Err(Error::Type {
-            expected: "number",
-            given: end.type_of().to_string(),
-        }),
-        (Atom(Vector(_)), start, _) => Err(Error::Type {
-            expected: "number",
-            given: start.type_of().to_string(),
-        }),
-        (v, _, _) => Err(Error::Type {
-            expected: "vector",
-            given: v.type_of().to_string(),
-        }),
-    }
+    let elements = vec.borrow()[start..end].to_vec();
+    Ok(Atom(Vector(elements.into())))
 }
 
 fn vector_head(v: SExp, end: SExp) -> Result<SExp, Error> {
-    match (v, end) {
-        (Atom(Vector(vec)), Atom(Number(n1))) => {
-            let i1 = n1.into();
-            if i1 >= vec.len() {
-                return Err(Error::Index { i: i1 });
-            }
+    let vec = vector(v)?;
+    let end = index(end)?;
+    bounds(0, end, vec.len())?;
 
-            Ok(Atom(Vector(vec[..i1].to_vec())))
-        }
-        (Atom(Vector(_)), end) => Err(Error::Type {
-            expected: "number",
-            given: end.type_of().to_string(),
-        }),
-        (v, _) => Err(Error::Type {
-            expected: "vector",
-            given: v.type_of().to_string(),
-        }),
-    }
+    let elements = vec.borrow()[..end].to_vec();
+    Ok(Atom(Vector(elements.into())))
 }
 
 fn vector_tail(v: SExp, start: SExp) -> Result<SExp, Error> {
-    match (v, start) {
-        (Atom(Vector(vec)), Atom(Number(n0))) => {
-            let i0 = n0.into();
-            if i0 >= vec.len() {
-                return Err(Error::Index { i: i0 });
-            }
+    let vec = vector(v)?;
+    let start = index(start)?;
+    bounds(start, vec.len(), vec.len())?;
 
-            Ok(Atom(Vector(vec[i0..].to_vec())))
-        }
-        (Atom(Vector(_)), start) => Err(Error::Type {
-            expected: "number",
-            given: start.type_of().to_string(),
-        }),
-        (v, _) => Err(Error::Type {
-            expected: "vector",
-            given: v.type_of().to_string(),
-        }),
-    }
+    let elements = vec.borrow()[start..].to_vec();
+    Ok(Atom(Vector(elements.into())))
 }
 
 impl Context {
@@ -234,10 +304,16 @@ impl Context {
         define_with!(self, "vector?", is_vector, make_unary_expr);
         define_with!(self, "vector-length", vector_len, make_unary_expr);
         define_with!(self, "vector-ref", vector_ref, make_binary_expr);
-        define_ctx!(self, "vector-set!", vector_set, 3);
+        define_with!(self, "vector-set!", vector_set, make_ternary_expr);
+        define!(self, "vector-fill!", vector_fill, (2, 4));
+        define_with!(self, "vector-grow", vector_grow, make_binary_expr);
+        define_ctx!(self, "vector-binary-search", vector_binary_search, 3);
         define_ctx!(self, "vector-map", vector_map, 2);
         define_with!(self, "subvector", subvector, make_ternary_expr);
         define_with!(self, "vector-head", vector_head, make_binary_expr);
         define_with!(self, "vector-tail", vector_tail, make_binary_expr);
+
+        define_ctx!(self, "sort", sort, 2);
+        define_ctx!(self, "sort!", sort_in_place, 2);
     }
 }
@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::super::super::proc::{Arity, Func, Proc};
+use super::super::super::SExp::{self, Atom, Null};
+use super::super::super::{Error, Primitive, Result};
+use super::super::{application, Context};
+use super::parse_params;
+
+impl Context {
+    /// `(when test body ...)`: evaluate `body` if `test` is true.
+    pub(super) fn eval_when(&mut self, expr: SExp) -> Result {
+        self.eval_conditional(expr, true)
+    }
+
+    /// `(unless test body ...)`: evaluate `body` if `test` is false.
+    pub(super) fn eval_unless(&mut self, expr: SExp) -> Result {
+        self.eval_conditional(expr, false)
+    }
+
+    fn eval_conditional(&mut self, expr: SExp, when: bool) -> Result {
+        let (test, body) = expr.split_car()?;
+
+        Ok(self.eval_then(test, move |c, result| {
+            let truthy = !matches!(result, Atom(Primitive::Boolean(false)));
+            if truthy == when {
+                Ok(c.eval_defer(&body))
+            } else {
+                Ok(Atom(Primitive::Void))
+            }
+        }))
+    }
+
+    /// `(case-lambda (formals body ...) ...)`: make a procedure that runs the
+    /// first clause whose formals accept the arguments it is called with.
+    pub(super) fn eval_case_lambda(&mut self, expr: SExp) -> Result {
+        let mut clauses = Vec::new();
+        let mut min = None;
+        let mut max = Some(0);
+
+        for clause in expr {
+            let (signature, body) = clause.split_car()?;
+            let (params, rest) = parse_params(signature)?;
+
+            min = Some(min.map_or(params.len(), |m: usize| m.min(params.len())));
+            max = match (max, &rest) {
+                (Some(m), None) => Some(params.len().max(m)),
+                _ => None,
+            };
+
+            match self.make_proc(None, params, rest, body) {
+                Atom(Primitive::Procedure(p)) => clauses.push(p),
+                _ => unreachable!("`make_proc` always makes a procedure"),
+            }
+        }
+
+        let arity = match (min.unwrap_or(0), max) {
+            (min, Some(max)) => Arity::from((min, max)),
+            (min, None) => Arity::from((min,)),
+        };
+
+        let envt = self.envt.clone();
+        let dispatch = move |args: SExp| {
+            let n_args = args.len()?;
+            let clause = clauses
+                .iter()
+                .find(|p| p.check_arity(n_args).is_ok())
+                // report the mismatch against the first clause
+                .ok_or_else(|| clauses[0].check_arity(n_args).unwrap_err())?;
+
+            Ok(SExp::from(Proc::new::<_, _, &str>(
+                Func::Tail {
+                    body: Rc::new(application(clause.clone().into(), args)),
+                    envt: envt.clone(),
+                },
+                0,
+                None,
+            )))
+        };
+
+        Ok(SExp::from(Proc::new::<_, _, &str>(
+            Func::Pure(Rc::new(dispatch)),
+            arity,
+            None,
+        )))
+    }
+
+    /// `(fluid-let ((var value) ...) body ...)`: assign each `var` its
+    /// `value` for the dynamic extent of `body`, restoring the old values
+    /// whenever that extent is left and the new ones when it is re-entered.
+    pub(super) fn eval_fluid_let(&mut self, expr: SExp) -> Result {
+        let (defn_list, body) = expr.split_car()?;
+        let (vars, values) = split_bindings(defn_list)?;
+        let vars = vars
+            .into_iter()
+            .map(|var| match var {
+                Atom(Primitive::Symbol(sym)) => Ok(sym),
+                other => Err(Error::Type {
+                    expected: "symbol",
+                    given: other.type_of().to_string(),
+                }),
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        self.eval_list(values, move |c, values| {
+            let envt = c.envt.clone();
+            // only assignments are made, so every variable must be bound
+            if let Some(var) = vars.iter().find(|var| envt.get(var).is_none()) {
+                return Err(Error::UndefinedSymbol { sym: var.clone() });
+            }
+
+            let cells = vars
+                .iter()
+                .cloned()
+                .zip(values)
+                .map(|(var, value)| (var, RefCell::new(value)))
+                .collect::<Vec<_>>();
+
+            // exchange the values held by the variables with the saved ones
+            let swap = Rc::new(move |_: SExp| {
+                for (var, cell) in &cells {
+                    let old = envt.set(var, cell.borrow().clone())?;
+                    *cell.borrow_mut() = old;
+                }
+                Ok(Atom(Primitive::Void))
+            });
+
+            swap(Null)?;
+            let restore = swap.clone();
+            c.push_then_values(move |_, val| {
+                restore(Null)?;
+                Ok(val)
+            });
+
+            let swap = SExp::from(Proc::new::<_, _, &str>(Func::Pure(swap), 0, None));
+            c.install_winder(swap.clone(), swap);
+            Ok(c.eval_defer(&body))
+        })
+    }
+
+    /// `(parameterize ((param value) ...) body ...)`: evaluate `body` with
+    /// each parameter object giving its (converted) `value`.
+    pub(super) fn eval_parameterize(&mut self, expr: SExp) -> Result {
+        let (defn_list, body) = expr.split_car()?;
+        let (params, values) = split_bindings(defn_list)?;
+
+        self.eval_list(params.into(), move |c, params| {
+            let params = params
+                .into_iter()
+                .map(|p| match p {
+                    Atom(Primitive::Procedure(Proc {
+                        func: Func::Parameter(p),
+                        ..
+                    })) => Ok(p),
+                    other => Err(Error::Type {
+                        expected: "parameter",
+                        given: other.type_of().to_string(),
+                    }),
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            // values are passed through each parameter's converter
+            let conversions = params
+                .iter()
+                .zip(values.clone())
+                .map(|(p, v)| match &p.converter {
+                    Some(converter) => Null.cons(v).cons(converter.clone()),
+                    None => v,
+                })
+                .collect();
+
+            let body = body.clone();
+            c.eval_list(conversions, move |c, values| {
+                // restores the outer values once the body returns
+                c.push_then_values(|_, val| Ok(val));
+                for (p, v) in params.iter().zip(values) {
+                    c.bind_parameter(p.clone(), v);
+                }
+                Ok(c.eval_defer(&body))
+            })
+        })
+    }
+}
+
+/// Split a list of `(name expr)` bindings into the names and the
+/// expressions.
+fn split_bindings(defn_list: SExp) -> std::result::Result<(Vec<SExp>, SExp), Error> {
+    let mut names = Vec::new();
+    let mut exprs = Vec::new();
+
+    for defn in defn_list {
+        let (name, tail) = defn.split_car()?;
+        names.push(name);
+        exprs.push(tail.car()?);
+    }
+
+    Ok((names, exprs.into()))
+}
@@ -0,0 +1,101 @@
+use std::rc::Rc;
+
+use super::super::super::proc::{Func, Proc};
+use super::super::super::SExp::{self, Atom, Null, Pair};
+use super::super::super::{Env, Error, Primitive, Result};
+use super::super::Context;
+
+impl Context {
+    /// `(with-exception-handler handler thunk)`: call `thunk` with `handler`
+    /// installed as the current exception handler.
+    pub(super) fn with_exception_handler(&mut self, expr: SExp) -> Result {
+        self.eval_list(expr, |c, args| {
+            let (handler, rest) = args.split_car()?;
+            let thunk = rest.car()?;
+
+            if !matches!(handler, Atom(Primitive::Procedure(_))) {
+                return Err(Error::Type {
+                    expected: "procedure",
+                    given: handler.type_of().to_string(),
+                });
+            }
+
+            // restores the current handlers once the thunk returns
+            c.push_then_values(|_, val| Ok(val));
+            c.install_handler(handler);
+            Ok(c.defer(Null.cons(thunk)))
+        })
+    }
+
+    /// `(raise-continuable obj)`: call the current exception handler on
+    /// `obj`, returning whatever it returns.
+    pub(super) fn raise_continuable(&mut self, expr: SExp) -> Result {
+        Ok(
+            self.eval_then(expr.car()?, |c, obj| match c.current_handler() {
+                Some(handler) => Ok(c.call_handler(&handler, obj, true)),
+                None => Err(Error::Raised {
+                    value: Box::new(obj),
+                }),
+            }),
+        )
+    }
+
+    /// `(guard (var clause ...) body ...)`: evaluate `body`, and if it raises
+    /// an exception, evaluate the `cond` clauses with `var` bound to the
+    /// raised object. If no clause matches, the object is raised again.
+    pub(super) fn eval_guard(&mut self, expr: SExp) -> Result {
+        let (spec, body) = expr.split_car()?;
+        let (var, clauses) = spec.split_car()?;
+        let var = match var {
+            Atom(Primitive::Symbol(sym)) => sym,
+            other => {
+                return Err(Error::Type {
+                    expected: "symbol",
+                    given: other.type_of().to_string(),
+                });
+            }
+        };
+
+        let mut clauses = clauses.into_iter().collect::<Vec<_>>();
+        let has_else = match clauses.last() {
+            Some(Pair(p)) => *p.head() == SExp::sym("else"),
+            _ => false,
+        };
+        if !has_else {
+            let reraise = sexp![SExp::sym("raise-continuable"), SExp::sym(&var)];
+            clauses.push(sexp![SExp::sym("else"), reraise]);
+        }
+        let handle = SExp::from(clauses).cons(SExp::sym("cond"));
+        let envt = self.envt.clone();
+
+        // the handler returns here, after restoring the handlers outside
+        self.push_then_values(|_, val| Ok(val));
+        let k = self.continuation();
+
+        let handler = move |args: SExp| {
+            let scope = Env::new(Some(envt.clone())).into_rc();
+            scope.define(&var, args.car()?);
+
+            // the clauses are evaluated after jumping out to the guard
+            let clauses = Proc::new::<_, _, &str>(
+                Func::Tail {
+                    body: Rc::new(handle.clone()),
+                    envt: scope,
+                },
+                0,
+                None,
+            );
+            Err(Error::Jump {
+                cont: Box::new(k.clone()),
+                value: Box::new(clauses.into()),
+            })
+        };
+
+        self.install_handler(SExp::from(Proc::new(
+            Func::Pure(Rc::new(handler)),
+            1,
+            Some("guard"),
+        )));
+        Ok(self.eval_defer(&body))
+    }
+}
@@ -1,12 +1,19 @@
-use std::collections::HashMap;
 use std::rc::Rc;
 
-use super::super::proc::{Func, Proc};
+use super::super::proc::{Arity, Func, Proc};
 use super::super::SExp::{self, Atom, Null, Pair};
 use super::super::{Error, Ns, Primitive, Result, SyntaxError};
-use super::Context;
-
+use super::{application, Context};
+
+mod derived;
+mod exception;
+mod promise;
+mod quasiquote;
+mod record;
+mod syntax;
 mod tests;
+mod values;
+mod wind;
 
 macro_rules! tup_ctx_env {
     ( $name:expr, $proc:expr, $arity:expr ) => {
@@ -26,29 +33,77 @@ impl Context {
         [
             tup_ctx_env!(
                 "eval",
-                |c: &mut Self, e: SExp| {
-                    let first_layer = c.eval(e.car()?)?;
-                    c.eval(first_layer)
-                },
+                |c: &mut Self, e: SExp| Ok(
+                    c.eval_then(e.car()?, |c, first_layer| Ok(c.defer(first_layer)))
+                ),
                 1
             ),
             tup_ctx_env!("apply", Self::do_apply, 2),
             tup_ctx_env!("and", Self::eval_and, (0,)),
             tup_ctx_env!("begin", Self::eval_begin, (0,)),
+            tup_ctx_env!("call-with-current-continuation", Self::call_cc, 1),
+            tup_ctx_env!("call/cc", Self::call_cc, 1),
+            tup_ctx_env!("call-with-values", Self::call_with_values, 2),
+            tup_ctx_env!("case-lambda", Self::eval_case_lambda, (1,)),
             tup_ctx_env!("case", Self::eval_case, (2,)),
             tup_ctx_env!("cond", Self::eval_cond, (0,)),
+            tup_ctx_env!("cons-stream", Self::eval_cons_stream, 2),
+            tup_ctx_env!("delay", |e, c| Self::eval_delay(e, c, false), 1),
+            tup_ctx_env!("delay-force", |e, c| Self::eval_delay(e, c, true), 1),
+            tup_ctx_env!("defmacro", Self::eval_defmacro, (3,)),
             tup_ctx_env!("do", Self::eval_do, (2,)),
+            tup_ctx_env!("dynamic-wind", Self::dynamic_wind, 3),
+            tup_ctx_env!("fluid-let", Self::eval_fluid_let, (2,)),
             tup_ctx_env!("define", Self::eval_define, (1,)),
+            tup_ctx_env!("define-macro", Self::eval_define_macro, (2,)),
+            tup_ctx_env!("define-record-type", Self::eval_define_record_type, (2,)),
+            tup_ctx_env!("define-syntax", Self::eval_define_syntax, 2),
+            tup_ctx_env!("define-values", Self::eval_define_values, 2),
+            tup_ctx_env!("guard", Self::eval_guard, (2,)),
             tup_ctx_env!("if", Self::eval_if, 3),
             tup_ctx_env!("lambda", |e, c| Self::eval_lambda(e, c, false), (2,)),
             tup_ctx_env!("let", Self::eval_let, (2,)),
             tup_ctx_env!("let*", Self::eval_let_star, (2,)),
-            tup_ctx_env!("letrec", Self::eval_let_star, (2,)),
+            tup_ctx_env!(
+                "let*-values",
+                |e, c| Self::eval_let_values(e, c, true),
+                (2,)
+            ),
+            tup_ctx_env!(
+                "let-syntax",
+                |e, c| Self::eval_let_syntax(e, c, false),
+                (2,)
+            ),
+            tup_ctx_env!(
+                "let-values",
+                |e, c| Self::eval_let_values(e, c, false),
+                (2,)
+            ),
+            tup_ctx_env!("letrec", |e, c| Self::eval_letrec(e, c, false), (2,)),
+            tup_ctx_env!("letrec*", |e, c| Self::eval_letrec(e, c, true), (2,)),
+            tup_ctx_env!(
+                "letrec-syntax",
+                |e, c| Self::eval_let_syntax(e, c, true),
+                (2,)
+            ),
+            tup_ctx_env!("macroexpand", |e, c| Self::eval_macroexpand(e, c, false), 1),
+            tup_ctx_env!(
+                "macroexpand-1",
+                |e, c| Self::eval_macroexpand(e, c, true),
+                1
+            ),
             tup_ctx_env!("named-lambda", |e, c| Self::eval_lambda(e, c, true), (2,)),
             tup_ctx_env!("or", Self::eval_or, (0,)),
+            tup_ctx_env!("parameterize", Self::eval_parameterize, (2,)),
             tup_ctx_env!("quasiquote", Self::eval_quasiquote, 1),
             tup_ctx_env!("quote", Self::eval_quote, 1),
+            tup_ctx_env!("raise-continuable", Self::raise_continuable, 1),
+            tup_ctx_env!("receive", Self::eval_receive, (3,)),
             tup_ctx_env!("set!", Self::eval_set, 2),
+            tup_ctx_env!("syntax-rules", Self::eval_syntax_rules, (1,)),
+            tup_ctx_env!("unless", Self::eval_unless, (1,)),
+            tup_ctx_env!("when", Self::eval_when, (1,)),
+            tup_ctx_env!("with-exception-handler", Self::with_exception_handler, 2),
         ]
         .iter()
         .cloned()
@@ -56,46 +111,53 @@ impl Context {
     }
 
     fn eval_and(&mut self, expr: SExp) -> Result {
-        let mut state = SExp::from(true);
-
-        for element in expr {
-            state = self.eval(element)?;
+        if expr.is_empty() {
+            return Ok(true.into());
+        }
 
-            if let Atom(Primitive::Boolean(false)) = state {
-                break;
-            }
+        let (head, tail) = expr.split_car()?;
+        // the last expression is in tail position
+        if tail.is_empty() {
+            return Ok(self.defer(head));
         }
 
-        Ok(state)
+        Ok(self.eval_then(head, move |c, state| match state {
+            Atom(Primitive::Boolean(false)) => Ok(state),
+            _ => c.eval_and(tail.clone()),
+        }))
     }
 
+    #[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
     fn eval_begin(&mut self, expr: SExp) -> Result {
-        let mut ret = Atom(Primitive::Undefined);
-        for exp in expr {
-            ret = self.eval(exp)?;
-        }
-        Ok(ret)
+        Ok(self.eval_defer(&expr))
+    }
+
+    fn call_cc(&mut self, expr: SExp) -> Result {
+        Ok(self.eval_then(expr.car()?, |c, proc| {
+            let k = c.continuation();
+            Ok(c.defer(Null.cons(k).cons(proc)))
+        }))
     }
 
     fn eval_case(&mut self, expr: SExp) -> Result {
         match expr {
-            Pair { head, tail } => {
-                let else_ = SExp::sym("else");
-                let hvl = self.eval(*head)?;
-
-                for case in *tail {
-                    if let Pair {
-                        head: objs,
-                        tail: body,
-                    } = case
-                    {
-                        if *objs == else_ || objs.iter().any(|e| *e == hvl) {
-                            return self.eval_defer(&*body);
+            Pair(p) => {
+                let (head, tail) = p.into_parts();
+                Ok(self.eval_then(head, move |c, hvl| {
+                    let else_ = SExp::sym("else");
+
+                    for case in tail.iter() {
+                        if let Pair(case) = case {
+                            let objs = case.car();
+                            if objs == else_ || objs.iter().any(|e| e == hvl) {
+                                return c.eval_clause(hvl, &case.cdr());
+                            }
                         }
                     }
-                }
 
-                self.eval_case(hvl)
+                    // falls through if no clause matches
+                    Ok(Atom(Primitive::Undefined))
+                }))
             }
             Atom(_) => Ok(Atom(Primitive::Undefined)),
             Null => Err(Error::ArityMin {
@@ -108,41 +170,54 @@ impl Context {
     fn eval_cond(&mut self, expr: SExp) -> Result {
         let else_ = SExp::sym("else");
 
-        for case in expr {
-            match case {
-                Pair {
-                    head: predicate,
-                    tail: consequent,
-                } => {
-                    // TODO: check if `else` clause is actually last
-                    if *predicate == else_ {
-                        return self.eval_defer(&*consequent);
-                    }
+        let (case, rest) = match expr {
+            // falls through if no valid predicates found
+            Null => return Ok(Atom(Primitive::Void)),
+            Pair(p) => p.into_parts(),
+            exp @ Atom(_) => return Err(SyntaxError::InvalidCond(exp).into()),
+        };
 
-                    match self.eval(*predicate)? {
-                        Atom(Primitive::Boolean(false)) => {
-                            continue;
-                        }
-                        _ => return self.eval_defer(&*consequent),
-                    }
-                }
-                exp => {
-                    return Err(SyntaxError::InvalidCond(exp).into());
+        match case {
+            Pair(p) => {
+                let (predicate, consequent) = p.into_parts();
+
+                // TODO: check if `else` clause is actually last
+                if predicate == else_ {
+                    return Ok(self.eval_defer(&consequent));
                 }
+
+                Ok(self.eval_then(predicate, move |c, result| match result {
+                    Atom(Primitive::Boolean(false)) => c.eval_cond(rest.clone()),
+                    // a clause with only a test returns the test's value
+                    _ if consequent.is_empty() => Ok(result),
+                    _ => c.eval_clause(result, &consequent),
+                }))
             }
+            exp => Err(SyntaxError::InvalidCond(exp).into()),
         }
+    }
 
-        // falls through if no valid predicates found
-        Ok(Atom(Primitive::Void))
+    /// Evaluate the body of a `cond` or `case` clause that was selected by
+    /// `value`. A body of the form `=> receiver` passes `value` to `receiver`
+    /// instead.
+    fn eval_clause(&mut self, value: SExp, body: &SExp) -> Result {
+        match body {
+            Pair(p) if *p.head() == SExp::sym("=>") => {
+                let receiver = p.cdr().car()?;
+                Ok(self.defer(application(receiver, Null.cons(value))))
+            }
+            _ => Ok(self.eval_defer(body)),
+        }
     }
 
     fn eval_define(&mut self, expr: SExp) -> Result {
         let (signature, defn) = expr.split_car()?;
 
-        let (sym, the_defn) = match signature {
+        match signature {
             // procedure
-            Pair { head, tail } => {
-                let sym = match *head {
+            Pair(p) => {
+                let (head, tail) = p.into_parts();
+                let sym = match head {
                     Atom(Primitive::Symbol(ref sym)) => sym.to_owned(),
                     other => {
                         return Err(Error::Type {
@@ -152,32 +227,34 @@ impl Context {
                     }
                 };
 
-                (sym, self.eval_lambda(defn.cons(tail.cons(*head)), true)?)
+                let the_defn = self.eval_lambda(defn.cons(tail.cons(head)), true)?;
+                self.define(&sym, the_defn);
+                Ok(Atom(Primitive::Undefined))
             }
             // simple value - can be nothing or something
             Atom(Primitive::Symbol(sym)) => {
-                match defn.len() {
+                match defn.len()? {
                     0 | 1 => (),
                     given => return Err(Error::ArityMax { expected: 1, given }),
                 }
 
-                match defn {
-                    Null => (sym, Atom(Primitive::Undefined)),
-                    p @ Pair { .. } => (sym, self.eval(p.car()?)?),
-                    other => (sym, self.eval(other)?),
-                }
-            }
-            other => {
-                return Err(Error::Type {
-                    expected: "symbol",
-                    given: other.type_of().to_string(),
-                });
-            }
-        };
+                let value = match defn {
+                    Null => Atom(Primitive::Undefined),
+                    p @ Pair { .. } => p.car()?,
+                    other => other,
+                };
 
-        // actually persist the definition to the environment
-        self.define(&sym, the_defn);
-        Ok(Atom(Primitive::Undefined))
+                // actually persist the definition to the environment
+                Ok(self.eval_then(value, move |c, the_defn| {
+                    c.define(&sym, the_defn);
+                    Ok(Atom(Primitive::Undefined))
+                }))
+            }
+            other => Err(Error::Type {
+                expected: "symbol",
+                given: other.type_of().to_string(),
+            }),
+        }
     }
 
     fn eval_do(&mut self, expr: SExp) -> Result {
@@ -185,19 +262,22 @@ impl Context {
         let (term, body) = rest.split_car()?;
 
         // get definitions for loop vars
-        let mut var_inits = HashMap::new();
-        let mut var_updates = HashMap::new();
+        let mut names = Vec::new();
+        let mut var_inits = Vec::new();
+        let mut var_updates = Vec::new();
 
         for var in vars {
             match var.split_car()? {
-                (Atom(Primitive::Symbol(s)), rest) => match rest.len() {
+                (Atom(Primitive::Symbol(s)), rest) => match rest.len()? {
                     1 => {
-                        var_inits.insert(s, self.eval(rest.car()?)?);
+                        names.push(s);
+                        var_inits.push(rest.car()?);
                     }
                     2 => {
                         let (defn, tail) = rest.split_car()?;
-                        var_inits.insert(s.clone(), self.eval(defn)?);
-                        var_updates.insert(s, tail.car()?);
+                        names.push(s.clone());
+                        var_inits.push(defn);
+                        var_updates.push((s, tail.car()?));
                     }
                     0 => {
                         return Err(Error::ArityMin {
@@ -219,42 +299,46 @@ impl Context {
         // termination condition and return value
         let (cond, return_expr) = term.split_car()?;
 
-        // add definitions to environment
-        self.push();
-        self.cont.borrow().env().extend(var_inits);
-
-        let result = 'eval: loop {
-            // check termination condition
-            match self.eval(cond.clone()) {
-                Ok(Atom(Primitive::Boolean(false))) => (),
-                Ok(_) => break 'eval self.eval_begin(return_expr),
-                err => break 'eval err,
-            }
-
-            // do each step
-            for exp in body.iter() {
-                if let Err(err) = self.eval(exp.to_owned()) {
-                    break 'eval Err(err);
-                }
-            }
+        let state = Rc::new(DoLoop {
+            cond,
+            return_expr,
+            body,
+            var_updates,
+        });
+
+        self.eval_list(var_inits.into(), move |c, inits| {
+            // add definitions to environment
+            c.push();
+            c.envt.extend(names.iter().cloned().zip(inits).collect());
+            Ok(c.do_iteration(state.clone()))
+        })
+    }
 
-            // update vars for next iteration:
-            // we don't want the new values to be in place while we
-            // evaluate subsequent step variables, so we hold them in a
-            // temporary map, then insert them all at once
-            let mut new_map = HashMap::new();
-            for (key, upd) in &var_updates {
-                let new_val = match self.eval(upd.to_owned()) {
-                    Ok(v) => v,
-                    err => break 'eval err,
-                };
-                new_map.insert(key.to_string(), new_val);
+    fn do_iteration(&mut self, state: Rc<DoLoop>) -> SExp {
+        // check termination condition
+        self.eval_then(state.cond.clone(), move |c, done| {
+            if let Atom(Primitive::Boolean(false)) = done {
+                // do each step, then update the loop vars
+                let next = state.clone();
+                c.push_then_values(move |c, _| c.do_update(next.clone()));
+                Ok(c.eval_defer(&state.body))
+            } else {
+                Ok(c.eval_defer(&state.return_expr))
             }
-            self.cont.borrow().env().extend(new_map);
-        };
+        })
+    }
 
-        self.pop();
-        result
+    fn do_update(&mut self, state: Rc<DoLoop>) -> Result {
+        // update vars for next iteration:
+        // we don't want the new values to be in place while we
+        // evaluate subsequent step variables, so we hold them
+        // until they are all ready, then insert them all at once
+        let (keys, updates): (Vec<_>, Vec<_>) = state.var_updates.iter().cloned().unzip();
+
+        self.eval_list(updates.into(), move |c, new_vals| {
+            c.envt.extend(keys.iter().cloned().zip(new_vals).collect());
+            Ok(c.do_iteration(state.clone()))
+        })
     }
 
     fn eval_if(&mut self, expr: SExp) -> Result {
@@ -262,66 +346,61 @@ impl Context {
         let (if_true, cdr) = cdr.split_car()?;
         let (if_false, _) = cdr.split_car()?;
 
-        let cevl = self.eval(condition)?;
-        Ok(self.defer(if let Atom(Primitive::Boolean(false)) = cevl {
-            if_false
-        } else {
-            if_true
+        Ok(self.eval_then(condition, move |c, cevl| {
+            Ok(c.defer(if let Atom(Primitive::Boolean(false)) = cevl {
+                if_false.clone()
+            } else {
+                if_true.clone()
+            }))
         }))
     }
 
     fn eval_lambda(&mut self, expr: SExp, is_named: bool) -> Result {
         let (signature, fn_body) = expr.split_car()?;
+        let (mut params, rest) = parse_params(signature)?;
 
-        match signature {
-            Pair { .. } | Null => (),
-            other => {
-                return Err(Error::Type {
-                    expected: "list",
-                    given: other.type_of().to_string(),
-                });
+        if is_named {
+            if params.is_empty() {
+                return Err(Error::NullList);
             }
-        }
 
-        let str_sig = signature
-            .into_iter()
-            .map(|e| {
-                if let Atom(Primitive::Symbol(sym)) = e {
-                    Ok(sym)
-                } else {
-                    Err(Error::Type {
-                        expected: "symbol",
-                        given: e.type_of().to_string(),
-                    })
-                }
-            })
-            .collect::<std::result::Result<Vec<_>, Error>>()?;
-
-        if is_named {
-            Ok(self.make_proc(Some(&str_sig[0]), str_sig[1..].to_vec(), fn_body))
+            let name = params.remove(0);
+            Ok(self.make_proc(Some(&name), params, rest, fn_body))
         } else {
-            Ok(self.make_proc(None, str_sig, fn_body))
+            Ok(self.make_proc(None, params, rest, fn_body))
         }
     }
 
-    fn make_proc(&self, name: Option<&str>, params: Vec<String>, fn_body: SExp) -> SExp {
-        let expected = params.len();
+    fn make_proc(
+        &self,
+        name: Option<&str>,
+        params: Vec<String>,
+        rest: Option<String>,
+        fn_body: SExp,
+    ) -> SExp {
+        let arity = if rest.is_some() {
+            Arity::from((params.len(),))
+        } else {
+            Arity::from(params.len())
+        };
+
         SExp::from(Proc::new(
             Func::Lambda {
-                body: Rc::new(fn_body),
-                envt: self.cont.borrow().env(),
+                body: Rc::new(hoist_defines(fn_body)),
+                envt: self.envt.clone(),
                 params,
+                rest,
             },
-            expected,
+            arity,
             name,
         ))
     }
 
-    pub(super) fn defer(&self, expr: SExp) -> SExp {
+    pub(crate) fn defer(&self, expr: SExp) -> SExp {
         SExp::from(Proc::new::<_, _, &str>(
             Func::Tail {
                 body: Rc::new(expr),
-                envt: self.cont.borrow().env(),
+                envt: self.envt.clone(),
             },
             0,
             None,
@@ -354,20 +433,20 @@ impl Context {
                 .unzip();
 
             self.push();
-            let proc = self.make_proc(Some(&let_name), params, statements);
+            let proc = self.make_proc(Some(&let_name), params, None, statements);
             self.define(&let_name, proc);
             let applic = SExp::from(inits).cons(Atom(Primitive::Symbol(let_name)));
-            let result = self.eval(applic);
-            self.pop();
-            result
+            Ok(self.defer(applic))
         } else {
-            let mut var_inits = Ns::new();
+            let mut names = Vec::new();
+            let mut var_inits = Vec::new();
 
             for defn in defn_list {
                 let (name, value) = defn.split_car()?;
                 let value = value.car()?;
                 if let Atom(Primitive::Symbol(n)) = name {
-                    var_inits.insert(n, self.eval(value)?);
+                    names.push(n);
+                    var_inits.push(value);
                 } else {
                     return Err(Error::Type {
                         expected: "symbol",
@@ -376,11 +455,11 @@ impl Context {
                 }
             }
 
-            self.push();
-            self.cont.borrow().env().extend(var_inits);
-            let result = self.eval_defer(&statements);
-            self.pop();
-            result
+            self.eval_list(var_inits.into(), move |c, inits| {
+                c.push();
+                c.envt.extend(names.iter().cloned().zip(inits).collect());
+                Ok(c.eval_defer(&statements))
+            })
         }
     }
 
@@ -388,48 +467,95 @@ impl Context {
         let (defn_list, statements) = expr.split_car()?;
 
         self.push();
+        self.eval_let_star_defns(defn_list, statements)
+    }
 
-        for defn in defn_list {
-            let err = self.eval_define(defn);
-
-            if err.is_err() {
-                self.pop();
-                return err;
-            }
+    fn eval_let_star_defns(&mut self, defn_list: SExp, statements: SExp) -> Result {
+        if defn_list.is_empty() {
+            return Ok(self.eval_defer(&statements));
         }
 
-        let result = self.eval_defer(&statements);
-        self.pop();
-        result
+        let (defn, rest) = defn_list.split_car()?;
+        self.push_then(move |c, _| c.eval_let_star_defns(rest.clone(), statements.clone()));
+        self.eval_define(defn)
     }
 
-    fn eval_or(&mut self, expr: SExp) -> Result {
-        for element in expr {
-            match self.eval(element)? {
-                Atom(Primitive::Boolean(false)) => continue,
-                exp => {
-                    return Ok(exp);
+    /// `(letrec ((name init) ...) body ...)`: evaluate each `init` in a new
+    /// scope where all of the names are bound, but unassigned until every
+    /// `init` has been evaluated. With `sequential` set, this is `letrec*`,
+    /// which assigns each name as soon as its `init` has been evaluated.
+    fn eval_letrec(&mut self, expr: SExp, sequential: bool) -> Result {
+        let (defn_list, statements) = expr.split_car()?;
+
+        let mut names = Vec::new();
+        let mut inits = Vec::new();
+        for defn in defn_list {
+            let (name, init) = defn.split_car()?;
+            match name {
+                Atom(Primitive::Symbol(n)) => names.push(n),
+                other => {
+                    return Err(Error::Type {
+                        expected: "symbol",
+                        given: other.type_of().to_string(),
+                    });
                 }
             }
+            inits.push(init);
         }
 
-        Ok(false.into())
+        self.push();
+        for name in &names {
+            self.define(name, Atom(Primitive::Undefined));
+        }
+
+        if sequential {
+            return self.eval_letrec_inits(names.into_iter().zip(inits).collect(), statements);
+        }
+
+        let inits = inits
+            .into_iter()
+            .map(SExp::car)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        self.eval_list(inits.into(), move |c, vals| {
+            for (name, val) in names.iter().zip(vals) {
+                c.define(name, val);
+            }
+            Ok(c.eval_defer(&statements))
+        })
     }
 
-    fn eval_quasiquote(&mut self, expr: SExp) -> Result {
-        match expr.car()? {
-            p @ Pair { .. } => p
-                .into_iter()
-                .map(|sub_expr| match sub_expr {
-                    Pair { head, tail } => match *head {
-                        Atom(Primitive::Symbol(ref s)) if s == "unquote" => self.eval(tail.car()?),
-                        _ => Ok(tail.cons(*head)),
-                    },
-                    _ => Ok(sub_expr),
-                })
-                .collect::<Result>(),
-            other => Ok(other),
+    fn eval_letrec_inits(&mut self, mut defns: Vec<(String, SExp)>, statements: SExp) -> Result {
+        if defns.is_empty() {
+            return Ok(self.eval_defer(&statements));
+        }
+
+        let (name, init) = defns.remove(0);
+        // a name without an init, as left by `(define x)`, stays unassigned
+        if init.is_empty() {
+            return self.eval_letrec_inits(defns, statements);
+        }
+
+        Ok(self.eval_then(init.car()?, move |c, val| {
+            c.define(&name, val);
+            c.eval_letrec_inits(defns.clone(), statements.clone())
+        }))
+    }
+
+    fn eval_or(&mut self, expr: SExp) -> Result {
+        if expr.is_empty() {
+            return Ok(false.into());
         }
+
+        let (head, tail) = expr.split_car()?;
+        // the last expression is in tail position
+        if tail.is_empty() {
+            return Ok(self.defer(head));
+        }
+
+        Ok(self.eval_then(head, move |c, state| match state {
+            Atom(Primitive::Boolean(false)) => c.eval_or(tail.clone()),
+            exp => Ok(exp),
+        }))
     }
 
     #[allow(clippy::unused_self)]
@@ -446,7 +572,6 @@ impl Context {
 
     fn eval_set(&mut self, expr: SExp) -> Result {
         let (name, tail) = expr.split_car()?;
-        let val = self.eval(tail.car()?)?;
 
         let sym = match name {
             Atom(Primitive::Symbol(sym)) => sym,
@@ -458,13 +583,112 @@ impl Context {
             }
         };
 
-        self.set(&sym, val)
+        Ok(self.eval_then(tail.car()?, move |c, val| c.set(&sym, val)))
     }
 
     fn do_apply(&mut self, expr: SExp) -> Result {
-        let (op, tail) = expr.split_car()?;
+        self.eval_list(expr, |c, args| {
+            let (proc, rest) = args.split_car()?;
+            let args = rest.car()?;
+            args.len()?;
+            Ok(c.defer(application(proc, args)))
+        })
+    }
+}
+
+/// Everything a `do` loop needs to run another iteration.
+struct DoLoop {
+    cond: SExp,
+    return_expr: SExp,
+    body: SExp,
+    var_updates: Vec<(String, SExp)>,
+}
+
+/// Walk a parameter list, which may end in a rest parameter.
+fn parse_params(signature: SExp) -> std::result::Result<(Vec<String>, Option<String>), Error> {
+    let mut params = Vec::new();
+    let mut remaining = signature;
+
+    loop {
+        match remaining {
+            Null => return Ok((params, None)),
+            Atom(Primitive::Symbol(sym)) => return Ok((params, Some(sym))),
+            Pair(p) => match p.into_parts() {
+                (Atom(Primitive::Symbol(sym)), tail) => {
+                    params.push(sym);
+                    remaining = tail;
+                }
+                (other, _) => {
+                    return Err(Error::Type {
+                        expected: "symbol",
+                        given: other.type_of().to_string(),
+                    });
+                }
+            },
+            other => {
+                return Err(Error::Type {
+                    expected: "list",
+                    given: other.type_of().to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Turn the internal definitions of a body into a `letrec*`, so that they are
+/// all in scope, if unassigned, from the start of the body. Definitions that
+/// follow an expression are left in place to assign their names when reached.
+fn hoist_defines(body: SExp) -> SExp {
+    let mut bindings = Vec::new();
+    let mut rest = body.clone();
+
+    while let Pair(p) = &rest {
+        match internal_binding(p.car()) {
+            Some(binding) => {
+                bindings.push(binding);
+                rest = p.cdr();
+            }
+            None => break,
+        }
+    }
+
+    for form in rest.iter() {
+        if let Some(Pair(binding)) = internal_binding(form) {
+            bindings.push(Null.cons(binding.car()));
+        }
+    }
+
+    // a body has to end with an expression for there to be a `letrec*` body
+    if bindings.is_empty() || !matches!(rest, Pair(_)) {
+        return body;
+    }
+
+    Null.cons(rest.cons(bindings.into()).cons(SExp::sym("letrec*")))
+}
 
-        let args = self.eval(tail.car()?)?;
-        self.eval(args.cons(op))
+/// The `letrec*` binding for an internal `define` form, if it is one.
+fn internal_binding(form: SExp) -> Option<SExp> {
+    let (head, tail) = form.split_car().ok()?;
+    if head != SExp::sym("define") {
+        return None;
+    }
+
+    let (signature, defn) = tail.split_car().ok()?;
+    match signature {
+        // (define (name . params) body ...)
+        Pair(p) => {
+            let name = p.car();
+            if !matches!(name, Atom(Primitive::Symbol(_))) {
+                return None;
+            }
+            let lambda = defn.cons(Pair(p)).cons(SExp::sym("named-lambda"));
+            Some(Null.cons(lambda).cons(name))
+        }
+        // (define name [value])
+        name @ Atom(Primitive::Symbol(_)) => match defn.len() {
+            Ok(0 | 1) => Some(defn.cons(name)),
+            _ => None,
+        },
+        _ => None,
     }
 }
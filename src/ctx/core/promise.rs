@@ -0,0 +1,28 @@
+use super::super::super::primitives::{Promise, PromiseState};
+use super::super::super::{Primitive, Result, SExp};
+use super::super::Context;
+
+impl Context {
+    /// `(delay expr)`: a promise to evaluate `expr` when it is forced. With
+    /// `lazy` set, this is `delay-force`, and `expr` should evaluate to
+    /// another promise.
+    pub(super) fn eval_delay(&mut self, expr: SExp, lazy: bool) -> Result {
+        Ok(self.delayed(expr.car()?, lazy))
+    }
+
+    /// `(cons-stream a b)`: the same as `(cons a (delay b))`.
+    pub(super) fn eval_cons_stream(&mut self, expr: SExp) -> Result {
+        let (head, rest) = expr.split_car()?;
+        let tail = self.delayed(rest.car()?, false);
+
+        Ok(self.eval_then(head, move |_, head| Ok(tail.clone().cons(head))))
+    }
+
+    fn delayed(&self, body: SExp, lazy: bool) -> SExp {
+        SExp::Atom(Primitive::Promise(Promise::new(PromiseState::Pending {
+            body,
+            envt: self.envt.clone(),
+            lazy,
+        })))
+    }
+}
@@ -0,0 +1,142 @@
+use std::rc::Rc;
+
+use super::super::super::proc::{Func, Proc};
+use super::super::super::SExp::{self, Atom, Null, Pair};
+use super::super::super::{Error, Primitive, Result};
+use super::super::Context;
+
+impl Context {
+    pub(super) fn eval_quasiquote(&mut self, expr: SExp) -> Result {
+        Ok(self.defer(Template::new().expand(expr.car()?, 1)?))
+    }
+}
+
+/// Rewrites a quasiquote template into an expression that builds it.
+///
+/// The procedures used to rebuild the template are referenced directly, not
+/// by name, so user definitions of e.g. `cons` cannot interfere.
+struct Template {
+    cons: SExp,
+    append: SExp,
+    list_to_vector: SExp,
+}
+
+impl Template {
+    fn new() -> Self {
+        Self {
+            cons: builder(cons, 2, "cons"),
+            append: builder(append, 2, "append"),
+            list_to_vector: builder(list_to_vector, 1, "list->vector"),
+        }
+    }
+
+    fn expand(&self, template: SExp, depth: usize) -> Result {
+        // nothing to fill in, so it can be used as-is
+        if !has_unquote(&template) {
+            return Ok(quote(template));
+        }
+
+        match template {
+            Pair(p) => match p.into_parts() {
+                (Atom(Primitive::Symbol(ref s)), Pair(x))
+                    if s == "unquote" && x.tail().is_empty() =>
+                {
+                    if depth == 1 {
+                        Ok(x.car())
+                    } else {
+                        self.keyword("unquote", x.car(), depth - 1)
+                    }
+                }
+                (Atom(Primitive::Symbol(ref s)), Pair(x))
+                    if s == "quasiquote" && x.tail().is_empty() =>
+                {
+                    self.keyword("quasiquote", x.car(), depth + 1)
+                }
+                (Atom(Primitive::Symbol(ref s)), Pair(x))
+                    if s == "unquote-splicing" && x.tail().is_empty() =>
+                {
+                    if depth == 1 {
+                        Err(Error::Type {
+                            expected: "list element",
+                            given: "unquote-splicing".to_string(),
+                        })
+                    } else {
+                        self.keyword("unquote-splicing", x.car(), depth - 1)
+                    }
+                }
+                (head, tail) => {
+                    let rest = self.expand(tail, depth)?;
+
+                    match head {
+                        Pair(p)
+                            if depth == 1
+                                && is_splice(&p.head())
+                                && p.tail().len().ok() == Some(1) =>
+                        {
+                            Ok(call(&self.append, vec![p.cdr().car()?, rest]))
+                        }
+                        head => Ok(call(&self.cons, vec![self.expand(head, depth)?, rest])),
+                    }
+                }
+            },
+            Atom(Primitive::Vector(v)) => Ok(call(
+                &self.list_to_vector,
+                vec![self.expand(v.to_vec().into(), depth)?],
+            )),
+            other => Ok(quote(other)),
+        }
+    }
+
+    /// Rebuild a `(keyword x)` form, with `x` expanded at the given depth.
+    fn keyword(&self, keyword: &str, x: SExp, depth: usize) -> Result {
+        let x = call(&self.cons, vec![self.expand(x, depth)?, quote(Null)]);
+        Ok(call(&self.cons, vec![quote(SExp::sym(keyword)), x]))
+    }
+}
+
+fn is_splice(expr: &SExp) -> bool {
+    matches!(expr, Atom(Primitive::Symbol(s)) if s == "unquote-splicing")
+}
+
+fn has_unquote(expr: &SExp) -> bool {
+    match expr {
+        Atom(Primitive::Symbol(s)) => s == "unquote" || s == "unquote-splicing",
+        Atom(Primitive::Vector(v)) => v.borrow().iter().any(has_unquote),
+        Pair(p) => has_unquote(&p.head()) || has_unquote(&p.tail()),
+        _ => false,
+    }
+}
+
+fn quote(expr: SExp) -> SExp {
+    Null.cons(expr).cons(SExp::sym("quote"))
+}
+
+fn call(func: &SExp, args: Vec<SExp>) -> SExp {
+    SExp::from(args).cons(func.clone())
+}
+
+fn builder(f: fn(SExp) -> Result, arity: usize, name: &str) -> SExp {
+    SExp::from(Proc::new(Func::Pure(Rc::new(f)), arity, Some(name)))
+}
+
+fn cons(args: SExp) -> Result {
+    let (head, tail) = args.split_car()?;
+    Ok(tail.car()?.cons(head))
+}
+
+fn append(args: SExp) -> Result {
+    let (list, tail) = args.split_car()?;
+    list.len()?;
+
+    let elements = list.into_iter().collect::<Vec<_>>();
+    Ok(elements.into_iter().rev().fold(tail.car()?, SExp::cons))
+}
+
+fn list_to_vector(args: SExp) -> Result {
+    let list = args.car()?;
+    list.len()?;
+
+    Ok(Atom(Primitive::Vector(
+        list.into_iter().collect::<Vec<_>>().into(),
+    )))
+}
@@ -0,0 +1,150 @@
+use std::rc::Rc;
+
+use super::super::super::primitives::{Record, RecordType};
+use super::super::super::proc::{Arity, Func, Proc};
+use super::super::super::SExp::{self, Atom, Null, Pair};
+use super::super::super::{Error, Primitive, Result, SyntaxError};
+use super::super::Context;
+
+impl Context {
+    /// `(define-record-type <name> (constructor field ...) predicate
+    /// (field accessor [modifier]) ...)`
+    ///
+    /// The constructor may also be written as a bare name, taking every
+    /// field in order, or as `#f` to leave it out; likewise for the
+    /// predicate. A field may be a bare name if it has no procedures.
+    pub(super) fn eval_define_record_type(&mut self, expr: SExp) -> Result {
+        let (type_name, rest) = expr.split_car()?;
+        let (ctor_spec, rest) = rest.split_car()?;
+        let (pred_name, field_specs) = rest.split_car()?;
+        let type_name = symbol(type_name)?;
+
+        let mut fields = Vec::new();
+        let mut procs = Vec::new();
+        for spec in field_specs {
+            let (field, names) = match spec {
+                Pair(p) => {
+                    let (field, names) = p.into_parts();
+                    (
+                        symbol(field)?,
+                        names.into_iter().map(symbol).collect::<Vec<_>>(),
+                    )
+                }
+                field => (symbol(field)?, Vec::new()),
+            };
+
+            procs.push((fields.len(), names));
+            fields.push(field);
+        }
+
+        let rtype = RecordType::new(&type_name, fields);
+
+        if let Some((name, args)) = constructor_spec(ctor_spec, &rtype)? {
+            let indices = args
+                .iter()
+                .map(|a| {
+                    rtype.field_index(a).ok_or_else(|| {
+                        Error::from(SyntaxError::UnknownField {
+                            record: type_name.clone(),
+                            field: a.clone(),
+                        })
+                    })
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let n_fields = rtype.fields().len();
+            let t = rtype.clone();
+
+            let ctor = move |args: SExp| {
+                let mut values = vec![Atom(Primitive::Boolean(false)); n_fields];
+                for (&i, val) in indices.iter().zip(args) {
+                    values[i] = val;
+                }
+                Ok(Atom(Primitive::Record(Record::new(t.clone(), values))))
+            };
+            self.define(&name, procedure(ctor, args.len(), &name));
+        }
+
+        if let Atom(Primitive::Symbol(name)) = pred_name {
+            let t = rtype.clone();
+            let pred = move |args: SExp| {
+                let is_t =
+                    matches!(args.car()?, Atom(Primitive::Record(r)) if *r.record_type() == t);
+                Ok(is_t.into())
+            };
+            self.define(&name, procedure(pred, 1, &name));
+        }
+
+        for (i, names) in procs {
+            let mut names = names.into_iter();
+
+            if let Some(accessor) = names.next().transpose()? {
+                let t = rtype.clone();
+                let get = move |args: SExp| {
+                    let r = record_of(&t, args.car()?)?;
+                    r.get(i).ok_or(Error::Index { i })
+                };
+                self.define(&accessor, procedure(get, 1, &accessor));
+            }
+
+            if let Some(modifier) = names.next().transpose()? {
+                let t = rtype.clone();
+                let set = move |args: SExp| {
+                    let (r, rest) = args.split_car()?;
+                    record_of(&t, r)?.set(i, rest.car()?);
+                    Ok(Atom(Primitive::Undefined))
+                };
+                self.define(&modifier, procedure(set, 2, &modifier));
+            }
+        }
+
+        self.define(&type_name, Atom(Primitive::RecordType(rtype)));
+        Ok(Atom(Primitive::Undefined))
+    }
+}
+
+fn symbol(e: SExp) -> std::result::Result<String, Error> {
+    match e {
+        Atom(Primitive::Symbol(s)) => Ok(s),
+        other => Err(Error::Type {
+            expected: "symbol",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
+
+/// The constructor's name and the fields it takes, if there is one.
+fn constructor_spec(
+    spec: SExp,
+    rtype: &RecordType,
+) -> std::result::Result<Option<(String, Vec<String>)>, Error> {
+    match spec {
+        Atom(Primitive::Boolean(false)) | Null => Ok(None),
+        Atom(Primitive::Symbol(name)) => Ok(Some((name, rtype.fields().to_vec()))),
+        spec => {
+            let (name, args) = spec.split_car()?;
+            let args = args
+                .into_iter()
+                .map(symbol)
+                .collect::<std::result::Result<_, _>>()?;
+            Ok(Some((symbol(name)?, args)))
+        }
+    }
+}
+
+fn record_of(rtype: &RecordType, e: SExp) -> std::result::Result<Record, Error> {
+    match e {
+        Atom(Primitive::Record(r)) if r.record_type() == rtype => Ok(r),
+        other => Err(Error::Type {
+            expected: "record of the matching type",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
+
+fn procedure<F, A>(f: F, arity: A, name: &str) -> SExp
+where
+    F: Fn(SExp) -> Result + 'static,
+    Arity: From<A>,
+{
+    SExp::from(Proc::new(Func::Pure(Rc::new(f)), arity, Some(name)))
+}
@@ -0,0 +1,682 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::super::super::env::Env;
+use super::super::super::proc::{Func, Proc};
+use super::super::super::SExp::{self, Atom, Null, Pair};
+use super::super::super::{Error, Primitive, Result, SyntaxError};
+use super::super::Context;
+
+/// Source of unique suffixes for renamed identifiers.
+static RENAMES: AtomicUsize = AtomicUsize::new(0);
+
+/// Forms whose operands bind new identifiers, for the purposes of renaming.
+const BINDING_FORMS: [&str; 7] = [
+    "lambda",
+    "named-lambda",
+    "let",
+    "let*",
+    "letrec",
+    "letrec*",
+    "do",
+];
+
+/// Forms that define the identifiers in their first operand.
+const DEFINING_FORMS: [&str; 2] = ["define", "define-values"];
+
+impl Context {
+    pub(super) fn eval_define_syntax(&mut self, expr: SExp) -> Result {
+        let (name, tail) = expr.split_car()?;
+        let keyword = match name {
+            Atom(Primitive::Symbol(sym)) => sym,
+            other => {
+                return Err(Error::Type {
+                    expected: "symbol",
+                    given: other.type_of().to_string(),
+                });
+            }
+        };
+
+        Ok(self.eval_then(tail.car()?, move |c, spec| {
+            c.define(&keyword, as_keyword(&keyword, spec)?);
+            Ok(Atom(Primitive::Undefined))
+        }))
+    }
+
+    pub(super) fn eval_let_syntax(&mut self, expr: SExp, is_rec: bool) -> Result {
+        let (defn_list, statements) = expr.split_car()?;
+
+        let mut names = Vec::new();
+        let mut specs = Vec::new();
+
+        for defn in defn_list {
+            let (name, spec) = defn.split_car()?;
+            if let Atom(Primitive::Symbol(n)) = name {
+                names.push(n);
+                specs.push(spec.car()?);
+            } else {
+                return Err(Error::Type {
+                    expected: "symbol",
+                    given: name.type_of().to_string(),
+                });
+            }
+        }
+
+        // recursive bindings get evaluated inside their own scope
+        if is_rec {
+            self.push();
+        }
+
+        self.eval_list(specs.into(), move |c, specs| {
+            if !is_rec {
+                c.push();
+            }
+
+            for (name, spec) in names.iter().zip(specs) {
+                c.define(name, as_keyword(name, spec)?);
+            }
+
+            Ok(c.eval_defer(&statements))
+        })
+    }
+
+    pub(super) fn eval_syntax_rules(&mut self, expr: SExp) -> Result {
+        let rules = Rc::new(SyntaxRules::new(expr, self.envt.clone())?);
+
+        Ok(SExp::from(Proc::new::<_, _, &str>(
+            Func::Macro(Rc::new(move |c, form| rules.expand(c, form))),
+            (0,),
+            None,
+        )))
+    }
+
+    pub(super) fn eval_define_macro(&mut self, expr: SExp) -> Result {
+        let (signature, body) = expr.split_car()?;
+
+        match signature {
+            // (define-macro (name params...) body...)
+            Pair(p) => {
+                let (head, tail) = p.into_parts();
+                let name = match head {
+                    Atom(Primitive::Symbol(ref sym)) => sym.clone(),
+                    other => {
+                        return Err(Error::Type {
+                            expected: "symbol",
+                            given: other.type_of().to_string(),
+                        });
+                    }
+                };
+
+                let transformer = self.eval_lambda(body.cons(tail.cons(head)), true)?;
+                self.define(&name, as_macro(&name, transformer)?);
+                Ok(Atom(Primitive::Undefined))
+            }
+            // (define-macro name transformer)
+            Atom(Primitive::Symbol(name)) => Ok(self.eval_then(body.car()?, move |c, t| {
+                c.define(&name, as_macro(&name, t)?);
+                Ok(Atom(Primitive::Undefined))
+            })),
+            other => Err(Error::Type {
+                expected: "symbol",
+                given: other.type_of().to_string(),
+            }),
+        }
+    }
+
+    pub(super) fn eval_defmacro(&mut self, expr: SExp) -> Result {
+        let (name, tail) = expr.split_car()?;
+        let (params, body) = tail.split_car()?;
+
+        self.eval_define_macro(body.cons(params.cons(name)))
+    }
+
+    pub(super) fn eval_macroexpand(&mut self, expr: SExp, once: bool) -> Result {
+        Ok(self.eval_then(expr.car()?, move |c, mut form| {
+            while let Some(expansion) = c.expand_macro(&form) {
+                form = expansion?;
+
+                if once {
+                    break;
+                }
+            }
+
+            Ok(form)
+        }))
+    }
+
+    /// Expand a form once, if its operator is a macro keyword.
+    fn expand_macro(&mut self, form: &SExp) -> Option<Result> {
+        let (keyword, operands) = match form {
+            Pair(p) => match &*p.head() {
+                Atom(Primitive::Symbol(sym)) => (sym.clone(), p.cdr()),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        match self.get(&keyword) {
+            Some(Atom(Primitive::Procedure(p))) => p.expand(operands, self),
+            _ => None,
+        }
+    }
+}
+
+/// Wrap a procedure so that it transforms unevaluated forms.
+fn as_macro(name: &str, transformer: SExp) -> Result {
+    match transformer {
+        Atom(Primitive::Procedure(ref p)) if !p.is_macro() => Ok(SExp::from(Proc::new(
+            Func::Macro(Rc::new(move |c, form| {
+                let quoted = form
+                    .into_iter()
+                    .map(|e| Null.cons(e).cons(SExp::sym("quote")))
+                    .collect::<SExp>();
+                c.eval(quoted.cons(transformer.clone()))
+            })),
+            (0,),
+            Some(name),
+        ))),
+        other => Err(Error::Type {
+            expected: "procedure",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
+
+/// Check that a value can be bound as syntax, naming it after its keyword.
+fn as_keyword(keyword: &str, spec: SExp) -> Result {
+    match spec {
+        Atom(Primitive::Procedure(p)) if p.is_macro() => {
+            Ok(SExp::from(Proc::new(p.func, (0,), Some(keyword))))
+        }
+        other => Err(Error::Type {
+            expected: "syntax transformer",
+            given: other.type_of().to_string(),
+        }),
+    }
+}
+
+/// What a pattern variable matched.
+#[derive(Clone)]
+enum Binding {
+    One(SExp),
+    /// One match per repetition of an ellipsis.
+    Many(Vec<Binding>),
+}
+
+type Bindings = HashMap<String, Binding>;
+
+/// Whether part of a template is code or quoted data. Identifiers in data
+/// are never renamed.
+#[derive(Clone, Copy)]
+enum Quoting {
+    Code,
+    Quoted,
+    /// Inside `quasiquote`, at the given nesting depth.
+    Quasi(usize),
+}
+
+/// What to substitute for identifiers that come from a template.
+struct Renames {
+    /// Fresh names for the identifiers that the template binds.
+    binders: HashMap<String, String>,
+    /// References into the definition environment for free identifiers
+    /// that are shadowed at the use site.
+    free: HashMap<String, SExp>,
+}
+
+struct Rule {
+    /// The pattern, not including the macro keyword.
+    pattern: SExp,
+    template: SExp,
+    /// Identifiers bound by the template itself, which get fresh names on
+    /// each expansion so they cannot capture variables from the use site.
+    introduced: Vec<String>,
+}
+
+/// A `syntax-rules` transformer.
+struct SyntaxRules {
+    ellipsis: String,
+    literals: Vec<String>,
+    rules: Vec<Rule>,
+    /// The environment the macro was defined in, where the free identifiers
+    /// of its templates are looked up.
+    envt: Rc<Env>,
+}
+
+impl SyntaxRules {
+    fn new(expr: SExp, envt: Rc<Env>) -> std::result::Result<Self, Error> {
+        // R7RS allows a custom ellipsis identifier before the literals
+        let (ellipsis, expr) = match expr.split_car()? {
+            (Atom(Primitive::Symbol(ell)), rest) => (ell, rest),
+            (literals, rest) => ("...".to_string(), rest.cons(literals)),
+        };
+        let (literals, rules) = expr.split_car()?;
+
+        let literals = literals
+            .into_iter()
+            .map(|e| match e {
+                Atom(Primitive::Symbol(sym)) => Ok(sym),
+                other => Err(Error::Type {
+                    expected: "symbol",
+                    given: other.type_of().to_string(),
+                }),
+            })
+            .collect::<std::result::Result<Vec<_>, Error>>()?;
+
+        let mut ret = Self {
+            ellipsis,
+            literals,
+            rules: Vec::new(),
+            envt,
+        };
+
+        for rule in rules {
+            let (pattern, tail) = rule.split_car()?;
+            let template = tail.car()?;
+
+            // the keyword position is never matched against
+            let pattern = match pattern {
+                Pair(p) => p.cdr(),
+                other => return Err(SyntaxError::InvalidTemplate(other).into()),
+            };
+
+            let vars = ret.pattern_vars(&pattern);
+            let mut introduced = Vec::new();
+            collect_binders(&template, &mut introduced);
+            introduced.retain(|s| !vars.contains(s) && *s != ret.ellipsis);
+            introduced.sort();
+            introduced.dedup();
+
+            ret.rules.push(Rule {
+                pattern,
+                template,
+                introduced,
+            });
+        }
+
+        Ok(ret)
+    }
+
+    fn expand(&self, ctx: &Context, form: SExp) -> Result {
+        for rule in &self.rules {
+            let mut bindings = Bindings::new();
+
+            if self.matches(&rule.pattern, &form, &mut bindings) {
+                let n = RENAMES.fetch_add(1, Ordering::Relaxed);
+                let binders = rule
+                    .introduced
+                    .iter()
+                    .map(|s| (s.clone(), format!("{s}%{n}")))
+                    .collect();
+
+                let mut symbols = Vec::new();
+                collect_symbols(&rule.template, &mut symbols);
+                symbols.retain(|s| !bindings.contains_key(s) && !rule.introduced.contains(s));
+                let free = symbols
+                    .into_iter()
+                    .filter_map(|s| self.reference(ctx, s))
+                    .collect();
+
+                let renames = Renames { binders, free };
+                return self.instantiate(&rule.template, &bindings, &renames, true, Quoting::Code);
+            }
+        }
+
+        Err(SyntaxError::NoMatchingRule(form).into())
+    }
+
+    /// If a free template identifier is shadowed at the use site, make an
+    /// expression that looks it up in the definition environment instead.
+    fn reference(&self, ctx: &Context, sym: String) -> Option<(String, SExp)> {
+        // keywords can't be shadowed, and other macros can't be referenced
+        // by value
+        if ctx.core.contains_key(&sym) || sym == self.ellipsis {
+            return None;
+        }
+
+        let defined = self.envt.iter().find(|ns| ns.binds(&sym));
+        let used = ctx.envt.iter().find(|ns| ns.binds(&sym));
+        match (defined, used) {
+            (None, None) => return None,
+            (Some(d), Some(u)) if std::ptr::eq(d, u) => return None,
+            _ => (),
+        }
+
+        let value = self.envt.get(&sym).or_else(|| ctx.lang.get(&sym).cloned());
+        if let Some(Atom(Primitive::Procedure(p))) = &value {
+            if p.is_macro() {
+                return None;
+            }
+        }
+
+        let thunk = Proc::new::<_, _, &str>(
+            Func::Lambda {
+                body: Rc::new(Null.cons(SExp::sym(&sym))),
+                envt: self.envt.clone(),
+                params: Vec::new(),
+                rest: None,
+            },
+            0,
+            None,
+        );
+
+        Some((sym, Null.cons(SExp::from(thunk))))
+    }
+
+    fn is_ellipsis(&self, expr: &SExp) -> bool {
+        matches!(expr, Atom(Primitive::Symbol(s)) if *s == self.ellipsis)
+    }
+
+    /// If `pattern` is of the form `(p <ellipsis> . rest)`, split it up.
+    fn split_ellipsis(&self, pattern: &SExp) -> Option<(SExp, SExp)> {
+        match pattern {
+            Pair(p) => match &*p.tail() {
+                Pair(t) if self.is_ellipsis(&t.head()) => Some((p.car(), t.cdr())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn pattern_vars(&self, pattern: &SExp) -> Vec<String> {
+        match pattern {
+            Atom(Primitive::Symbol(s)) => {
+                if s == "_" || *s == self.ellipsis || self.literals.contains(s) {
+                    Vec::new()
+                } else {
+                    vec![s.clone()]
+                }
+            }
+            Atom(Primitive::Vector(v)) => v
+                .borrow()
+                .iter()
+                .flat_map(|e| self.pattern_vars(e))
+                .collect(),
+            Pair(p) => {
+                let mut vars = self.pattern_vars(&p.head());
+                vars.extend(self.pattern_vars(&p.tail()));
+                vars
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn matches(&self, pattern: &SExp, form: &SExp, bindings: &mut Bindings) -> bool {
+        if let Some((repeated, rest)) = self.split_ellipsis(pattern) {
+            return self.matches_ellipsis(&repeated, &rest, form, bindings);
+        }
+
+        match (pattern, form) {
+            (Atom(Primitive::Symbol(s)), _) if s == "_" => true,
+            (Atom(Primitive::Symbol(s)), _) if self.literals.contains(s) => pattern == form,
+            (Atom(Primitive::Symbol(s)), _) => {
+                bindings.insert(s.clone(), Binding::One(form.clone()));
+                true
+            }
+            (Atom(Primitive::Vector(p)), Atom(Primitive::Vector(f))) => {
+                self.matches(&SExp::from(p.to_vec()), &SExp::from(f.to_vec()), bindings)
+            }
+            (Pair(p), Pair(f)) => {
+                self.matches(&p.head(), &f.head(), bindings)
+                    && self.matches(&p.tail(), &f.tail(), bindings)
+            }
+            _ => pattern == form,
+        }
+    }
+
+    fn matches_ellipsis(
+        &self,
+        repeated: &SExp,
+        rest: &SExp,
+        form: &SExp,
+        bindings: &mut Bindings,
+    ) -> bool {
+        let (items, last) = split_improper(form);
+        let min_after = split_improper(rest).0.len();
+
+        if items.len() < min_after {
+            return false;
+        }
+
+        let n_repeats = items.len() - min_after;
+        let mut matched = Vec::with_capacity(n_repeats);
+
+        for item in &items[..n_repeats] {
+            let mut b = Bindings::new();
+            if !self.matches(repeated, item, &mut b) {
+                return false;
+            }
+            matched.push(b);
+        }
+
+        for var in self.pattern_vars(repeated) {
+            let each = matched.iter_mut().filter_map(|b| b.remove(&var)).collect();
+            bindings.insert(var, Binding::Many(each));
+        }
+
+        let remaining = items[n_repeats..]
+            .iter()
+            .rev()
+            .fold(last, |acc, e| acc.cons(e.clone()));
+
+        self.matches(rest, &remaining, bindings)
+    }
+
+    fn instantiate(
+        &self,
+        template: &SExp,
+        bindings: &Bindings,
+        renames: &Renames,
+        use_ellipsis: bool,
+        quoting: Quoting,
+    ) -> Result {
+        match template {
+            Atom(Primitive::Symbol(s)) => match bindings.get(s) {
+                Some(Binding::One(e)) => Ok(e.clone()),
+                Some(Binding::Many(_)) => {
+                    Err(SyntaxError::InvalidTemplate(template.clone()).into())
+                }
+                None if matches!(quoting, Quoting::Code) => {
+                    if let Some(r) = renames.binders.get(s) {
+                        Ok(SExp::sym(r))
+                    } else {
+                        Ok(renames.free.get(s).unwrap_or(template).clone())
+                    }
+                }
+                None => Ok(template.clone()),
+            },
+            Atom(Primitive::Vector(v)) => Ok(Atom(Primitive::Vector(
+                self.instantiate(
+                    &SExp::from(v.to_vec()),
+                    bindings,
+                    renames,
+                    use_ellipsis,
+                    quoting,
+                )?
+                .into_iter()
+                .collect::<Vec<_>>()
+                .into(),
+            ))),
+            // `(... template)` escapes any ellipses inside it
+            Pair(p) if use_ellipsis && self.is_ellipsis(&p.head()) => {
+                self.instantiate(&p.cdr().car()?, bindings, renames, false, quoting)
+            }
+            Pair(p) => {
+                let head = p.car();
+                let inner = quoting.enter(template);
+
+                // count how many ellipses follow this element
+                let mut depth = 0;
+                let mut rest = p.cdr();
+                while let Pair(next) = &rest {
+                    if !use_ellipsis || !self.is_ellipsis(&next.head()) {
+                        break;
+                    }
+                    depth += 1;
+                    rest = next.cdr();
+                }
+
+                let rest = self.instantiate(&rest, bindings, renames, use_ellipsis, inner)?;
+
+                if depth == 0 {
+                    return Ok(rest.cons(self.instantiate(
+                        &head,
+                        bindings,
+                        renames,
+                        use_ellipsis,
+                        quoting,
+                    )?));
+                }
+
+                Ok(self
+                    .repeat(&head, bindings, renames, depth, quoting)?
+                    .into_iter()
+                    .rev()
+                    .fold(rest, SExp::cons))
+            }
+            _ => Ok(template.clone()),
+        }
+    }
+
+    /// Instantiate a template followed by `depth` ellipses.
+    fn repeat(
+        &self,
+        template: &SExp,
+        bindings: &Bindings,
+        renames: &Renames,
+        depth: usize,
+        quoting: Quoting,
+    ) -> std::result::Result<Vec<SExp>, Error> {
+        if depth == 0 {
+            return Ok(vec![
+                self.instantiate(template, bindings, renames, true, quoting)?
+            ]);
+        }
+
+        let vars = self
+            .pattern_vars(template)
+            .into_iter()
+            .filter_map(|v| match bindings.get(&v) {
+                Some(Binding::Many(each)) => Some((v, each)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let n_repeats = match vars.first() {
+            Some((_, each)) if vars.iter().all(|(_, e)| e.len() == each.len()) => each.len(),
+            _ => return Err(SyntaxError::InvalidTemplate(template.clone()).into()),
+        };
+
+        let mut ret = Vec::new();
+        for i in 0..n_repeats {
+            let mut b = bindings.clone();
+            for (var, each) in &vars {
+                b.insert(var.clone(), each[i].clone());
+            }
+            ret.extend(self.repeat(template, &b, renames, depth - 1, quoting)?);
+        }
+
+        Ok(ret)
+    }
+}
+
+impl Quoting {
+    /// How the operands of `form` are treated, if it appears here.
+    fn enter(self, form: &SExp) -> Self {
+        let keyword = match form {
+            Pair(p) if matches!(p.cdr(), Pair(ref t) if t.tail().is_empty()) => p.car(),
+            _ => return self,
+        };
+        let keyword = match &keyword {
+            Atom(Primitive::Symbol(s)) => s.as_str(),
+            _ => return self,
+        };
+
+        match (keyword, self) {
+            ("quote", Quoting::Code) => Quoting::Quoted,
+            ("quasiquote", Quoting::Code) => Quoting::Quasi(1),
+            ("quasiquote", Quoting::Quasi(d)) => Quoting::Quasi(d + 1),
+            ("unquote" | "unquote-splicing", Quoting::Quasi(1)) => Quoting::Code,
+            ("unquote" | "unquote-splicing", Quoting::Quasi(d)) => Quoting::Quasi(d - 1),
+            _ => self,
+        }
+    }
+}
+
+/// Split a (possibly improper) list into its elements and its final cdr.
+fn split_improper(list: &SExp) -> (Vec<SExp>, SExp) {
+    let mut items = Vec::new();
+    let mut rest = list.clone();
+
+    while let Pair(p) = rest {
+        items.push(p.car());
+        rest = p.cdr();
+    }
+
+    (items, rest)
+}
+
+/// Find every identifier that a template binds with `lambda`, `let`,
+/// `define`, etc.
+fn collect_binders(template: &SExp, binders: &mut Vec<String>) {
+    let (head, tail) = match template {
+        Pair(p) => (p.car(), p.cdr()),
+        _ => return,
+    };
+
+    if let Atom(Primitive::Symbol(form)) = &head {
+        // nothing is bound inside quoted data
+        if form == "quote" || form == "quasiquote" {
+            return;
+        }
+
+        // both `(define name ...)` and `(define (name params ...) ...)`
+        if DEFINING_FORMS.contains(&form.as_str()) {
+            if let Pair(t) = &tail {
+                collect_symbols(&t.head(), binders);
+            }
+        }
+
+        if BINDING_FORMS.contains(&form.as_str()) {
+            let mut targets = split_improper(&tail).0.into_iter();
+
+            // named `let`
+            let mut params = targets.next();
+            if let (Some(name @ Atom(Primitive::Symbol(_))), "let") = (&params, form.as_str()) {
+                collect_symbols(name, binders);
+                params = targets.next();
+            }
+
+            match (form.as_str(), params) {
+                ("lambda" | "named-lambda", Some(ps)) => collect_symbols(&ps, binders),
+                (_, Some(defns)) => {
+                    for defn in split_improper(&defns).0 {
+                        if let Pair(d) = defn {
+                            collect_symbols(&d.head(), binders);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    collect_binders(&head, binders);
+    let mut rest = tail;
+    while let Pair(p) = rest {
+        collect_binders(&p.head(), binders);
+        rest = p.cdr();
+    }
+}
+
+fn collect_symbols(expr: &SExp, symbols: &mut Vec<String>) {
+    match expr {
+        Atom(Primitive::Symbol(s)) => symbols.push(s.clone()),
+        Pair(p) => {
+            collect_symbols(&p.head(), symbols);
+            collect_symbols(&p.tail(), symbols);
+        }
+        _ => (),
+    }
+}
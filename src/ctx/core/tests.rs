@@ -1,5 +1,9 @@
 #![cfg(test)]
 
+use std::rc::Rc;
+
+use super::super::super::cont::Cont;
+use super::super::super::primitives::Num;
 use super::SExp::{self, Null};
 use super::*;
 
@@ -56,6 +60,29 @@ fn quasiquote() {
         ],
         sexp!['a', "hello world", true, 6]
     );
+
+    // splicing, in lists and vectors
+    let spliced = sexp![s("unquote-splicing"), sexp![s("list"), 2, 3]];
+    assert_eval_eq!(
+        sexp![s("quasiquote"), sexp![1, spliced.clone(), 4]],
+        sexp![1, 2, 3, 4]
+    );
+    assert_eval_eq!(
+        sexp![
+            s("quasiquote"),
+            Atom(Primitive::Vector(vec![1.into(), spliced].into()))
+        ],
+        Primitive::Vector(vec![1.into(), 2.into(), 3.into()].into())
+    );
+    // can only splice lists
+    assert!(eval(sexp![
+        s("quasiquote"),
+        sexp![1, sexp![s("unquote-splicing"), 2]]
+    ])
+    .is_err());
+    // inner levels are left alone
+    let inner = sexp![s("quasiquote"), sexp![s("unquote"), s("x")]];
+    assert_eval_eq!(sexp![s("quasiquote"), inner.clone()], inner);
 }
 
 #[test]
@@ -293,4 +320,238 @@ fn lambda() {
         ],
         121
     );
+    // rest parameters
+    assert_eval_eq!(sexp![sexp![s("lambda"), s("x"), s("x")]], ());
+    assert_eval_eq!(sexp![sexp![s("lambda"), s("x"), s("x")], 1, 2], sexp![1, 2]);
+    assert_eval_eq!(
+        sexp![
+            sexp![s("lambda"), SExp::from((s("x"), s("y"))), s("y")],
+            1,
+            2,
+            3
+        ],
+        sexp![2, 3]
+    );
+    assert!(eval(sexp![sexp![
+        s("lambda"),
+        SExp::from((s("x"), s("y"))),
+        s("y")
+    ]])
+    .is_err());
+}
+
+#[test]
+fn call_cc() {
+    // escaping
+    assert_eval_eq!(
+        sexp![
+            s("+"),
+            1,
+            sexp![
+                s("call/cc"),
+                sexp![
+                    s("lambda"),
+                    sexp![s("k")],
+                    sexp![s("+"), 10, sexp![s("k"), 2]]
+                ]
+            ]
+        ],
+        3
+    );
+    // returning normally
+    assert_eval_eq!(
+        sexp![
+            s("call-with-current-continuation"),
+            sexp![s("lambda"), sexp![s("k")], 5]
+        ],
+        5
+    );
+    // argument must be a procedure
+    assert!(eval(sexp![s("call/cc"), 5]).is_err());
+}
+
+#[test]
+fn syntax_rules() {
+    let rules = sexp![
+        s("syntax-rules"),
+        (),
+        sexp![
+            sexp![s("_"), s("a"), s("...")],
+            sexp![s("+"), s("a"), s("...")]
+        ]
+    ];
+    // transformers are values
+    assert!(eval(rules.clone()).is_ok());
+    // ellipses collect any number of operands
+    assert_eval_eq!(
+        sexp![
+            s("let-syntax"),
+            sexp![sexp![s("sum"), rules.clone()]],
+            sexp![s("sum"), 1, 2, 3]
+        ],
+        6
+    );
+    assert_eval_eq!(
+        sexp![
+            s("let-syntax"),
+            sexp![sexp![s("sum"), rules]],
+            sexp![s("sum")]
+        ],
+        0
+    );
+    // only transformers can be bound as syntax
+    assert!(eval(sexp![s("define-syntax"), s("x"), 3]).is_err());
+}
+
+#[test]
+fn define_macro() {
+    let mut ctx = Context::base();
+    // (define-macro (swap x y) (list y x))
+    ctx.eval(sexp![
+        s("define-macro"),
+        sexp![s("swap"), s("x"), s("y")],
+        sexp![s("list"), s("y"), s("x")]
+    ])
+    .unwrap();
+
+    // operands are passed in unevaluated
+    assert_eq!(
+        ctx.eval(sexp![s("swap"), 3, s("add1")]).unwrap(),
+        SExp::from(4)
+    );
+    assert_eq!(
+        ctx.eval(sexp![
+            s("macroexpand-1"),
+            sexp![s("quote"), sexp![s("swap"), 3, s("add1")]]
+        ])
+        .unwrap(),
+        sexp![s("add1"), 3]
+    );
+    // non-macro forms are left alone
+    assert_eq!(
+        ctx.eval(sexp![
+            s("macroexpand"),
+            sexp![s("quote"), sexp![s("add1"), 3]]
+        ])
+        .unwrap(),
+        sexp![s("add1"), 3]
+    );
+    // transformers must be procedures
+    assert!(ctx.eval(sexp![s("define-macro"), s("m"), 3]).is_err());
+}
+
+#[test]
+fn define_record_type() {
+    let mut ctx = Context::base();
+    ctx.run("(define-record-type <point> (make-point x y) point? (x point-x) (y point-y))")
+        .unwrap();
+
+    let p = ctx.run("(make-point 1 2)").unwrap().to_string();
+    assert!(p.starts_with("#[point ") && p.ends_with(']'), "{}", p);
+    assert!(ctx
+        .run("<point>")
+        .unwrap()
+        .to_string()
+        .starts_with("#[record-type "));
+
+    // constructor arguments must name fields
+    let err = ctx
+        .run("(define-record-type q (make-q z) q? (x q-x))")
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Record type q has no field z");
+    assert!(ctx.run("make-q").is_err());
+}
+
+#[test]
+fn uncaught_raise() {
+    let mut ctx = Context::base();
+
+    match ctx.run("(raise 'boom)") {
+        Err(Error::Raised { value }) => assert_eq!(*value, s("boom")),
+        other => panic!("expected a raised object, got {:?}", other),
+    }
+    // errors leaving a handler are raised as conditions
+    let err = ctx
+        .run("(with-exception-handler (lambda (e) e) (lambda () (error \"bad:\" 1)))")
+        .unwrap_err();
+    assert_eq!(err.to_string(), "bad: 1");
+    // and the handlers are gone afterwards
+    assert!(ctx.run("(car 5)").is_err());
+}
+
+#[test]
+fn multiple_values() {
+    let mut ctx = Context::base();
+
+    // several values print as several results
+    assert_eq!(
+        ctx.run("(values 1 \"two\" 'three)").unwrap().to_string(),
+        "1\ntwo\nthree"
+    );
+    assert_eq!(ctx.run("(values)").unwrap().to_string(), "");
+    // and a single value is just that value
+    assert_eq!(ctx.run("(values 4)").unwrap(), SExp::from(4));
+}
+
+/// How many frames are waiting on the current evaluation.
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+fn frames(ctx: &mut Context, _: SExp) -> Result {
+    let depth = ctx.cont.as_ref().map_or(0, |k| Cont::iter(k).count());
+    Ok(SExp::from(Num::from(depth)))
+}
+
+#[test]
+fn tail_calls() {
+    let mut ctx = Context::base();
+    ctx.lang.insert(
+        "frames".to_string(),
+        Proc::new(Func::Ctx(Rc::new(frames)), 0, Some("frames")).into(),
+    );
+
+    // each loop calls itself from a tail position of a different form, so
+    // the frames waiting at the bottom don't depend on how deep it went
+    let loops = [
+        "(define (loop n) (if (= n 0) (frames) (loop (- n 1))))",
+        "(define (loop n) (if (= n 0) (frames) (and #t (loop (- n 1)))))",
+        "(define (loop n) (if (= n 0) (frames) (or #f (loop (- n 1)))))",
+        "(define (loop n) (cond ((= n 0) (frames)) (else (loop (- n 1)))))",
+        "(define (loop n) (case n ((0) (frames)) (else (loop (- n 1)))))",
+        "(define (loop n) (if (= n 0) (frames) (begin 1 (loop (- n 1)))))",
+        "(define (loop n) (if (= n 0) (frames) (let ((m (- n 1))) (loop m))))",
+        "(define (loop n) (if (= n 0) (frames) (let* ((m (- n 1))) (loop m))))",
+        "(define (loop n) (if (= n 0) (frames) (letrec ((m (- n 1))) (loop m))))",
+        "(define (loop n) (let lp ((m n)) (if (= m 0) (frames) (lp (- m 1)))))",
+        "(define (loop n) (if (= n 0) (frames) (do ((i 0 (+ i 1))) ((= i 1) (loop (- n 1))))))",
+        "(define (loop n) (if (= n 0) (frames) (apply loop (list (- n 1)))))",
+        "(define (loop n) (if (= n 0) (frames) (foldl (lambda (_ m) (loop m)) 0 (list (- n 1)))))",
+        "(define (loop n) (if (= n 0) (frames) (call-with-values (lambda () (- n 1)) loop)))",
+        "(define (loop n) (if (= n 0) (frames) (receive (m) (- n 1) (loop m))))",
+        "(define (loop n) (if (= n 0) (frames) (eval (list 'loop (- n 1)))))",
+        "(define (loop n) (define m (- n 1)) (if (= n 0) (frames) (loop m)))",
+        "(define (loop n) (if (= n 0) (frames) (when #t (loop (- n 1)))))",
+        "(define (loop n) (if (= n 0) (frames) (unless #f (loop (- n 1)))))",
+        "(define (loop n) (cond ((= n 0) (frames)) ((- n 1) => loop)))",
+        "(define (loop n) (case n ((0) (frames)) (else => (lambda (m) (loop (- m 1))))))",
+        "(define loop (case-lambda ((n) (loop n 0)) ((n _) (if (= n 0) (frames) (loop (- n 1) 0)))))",
+    ];
+
+    for code in loops.iter() {
+        ctx.run(code).unwrap();
+        let shallow = ctx.run("(loop 1)").unwrap();
+        let deep = ctx.run("(loop 1000)").unwrap();
+        assert_eq!(shallow, deep, "{}", code);
+    }
+}
+
+#[test]
+fn deep_recursion_through_builtins() {
+    let mut ctx = Context::base();
+
+    // not tail calls, but they shouldn't use up the Rust stack either
+    ctx.run("(define (d n) (if (= n 0) 0 (+ 1 (car (map (lambda (x) (d (- n 1))) '(1))))))")
+        .unwrap();
+    assert_eq!(ctx.run("(d 20000)").unwrap(), SExp::from(20000));
+    ctx.run("(define (a n) (if (= n 0) 0 (+ 1 (apply a (list (- n 1))))))")
+        .unwrap();
+    assert_eq!(ctx.run("(a 20000)").unwrap(), SExp::from(20000));
 }
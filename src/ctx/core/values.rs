@@ -0,0 +1,114 @@
+use super::super::super::SExp::{self, Atom, Null};
+use super::super::super::{Error, Primitive, Result};
+use super::super::{application, Context};
+use super::{parse_params, Arity};
+
+/// The values an expression returned, however many there were.
+fn spread(vals: SExp) -> SExp {
+    vals.into_values().into()
+}
+
+impl Context {
+    /// `(call-with-values producer consumer)`: call `consumer` with the values
+    /// returned by calling `producer`.
+    pub(super) fn call_with_values(&mut self, expr: SExp) -> Result {
+        self.eval_list(expr, |c, args| {
+            let (producer, rest) = args.split_car()?;
+            let consumer = rest.car()?;
+
+            Ok(c.eval_then_values(Null.cons(producer), move |c, vals| {
+                Ok(c.defer(application(consumer.clone(), spread(vals))))
+            }))
+        })
+    }
+
+    /// `(receive formals expr body ...)`: evaluate `body` with the values of
+    /// `expr` bound to `formals`, as if they were passed to a lambda.
+    pub(super) fn eval_receive(&mut self, expr: SExp) -> Result {
+        let (formals, rest) = expr.split_car()?;
+        let (vals, body) = rest.split_car()?;
+        let (params, rest) = parse_params(formals)?;
+
+        Ok(self.eval_then_values(vals, move |c, vals| {
+            let proc = c.make_proc(None, params.clone(), rest.clone(), body.clone());
+            Ok(c.defer(application(proc, spread(vals))))
+        }))
+    }
+
+    /// `(let-values (((formals) expr) ...) body ...)`: like `let`, binding
+    /// each set of formals to the values of its expression. With `sequential`
+    /// set, this is `let*-values`.
+    pub(super) fn eval_let_values(&mut self, expr: SExp, sequential: bool) -> Result {
+        let (defn_list, body) = expr.split_car()?;
+
+        let mut formals = Vec::new();
+        let mut inits = Vec::new();
+        for defn in defn_list {
+            let (names, init) = defn.split_car()?;
+            formals.push(names);
+            inits.push(init.car()?);
+        }
+
+        if sequential {
+            self.push();
+            return self.let_values_star(formals.into(), inits.into(), body);
+        }
+
+        self.eval_list_values(inits.into(), move |c, vals| {
+            c.push();
+            for (names, vals) in formals.iter().zip(vals) {
+                c.bind_values(names.clone(), vals)?;
+            }
+            Ok(c.eval_defer(&body))
+        })
+    }
+
+    fn let_values_star(&mut self, formals: SExp, inits: SExp, body: SExp) -> Result {
+        if formals.is_empty() {
+            return Ok(self.eval_defer(&body));
+        }
+
+        let (names, formals) = formals.split_car()?;
+        let (init, inits) = inits.split_car()?;
+        Ok(self.eval_then_values(init, move |c, vals| {
+            c.bind_values(names.clone(), vals)?;
+            c.let_values_star(formals.clone(), inits.clone(), body.clone())
+        }))
+    }
+
+    /// `(define-values formals expr)`: define each of `formals` to the values
+    /// of `expr`.
+    pub(super) fn eval_define_values(&mut self, expr: SExp) -> Result {
+        let (formals, rest) = expr.split_car()?;
+        let vals = rest.car()?;
+
+        Ok(self.eval_then_values(vals, move |c, vals| {
+            c.bind_values(formals.clone(), vals)?;
+            Ok(Atom(Primitive::Undefined))
+        }))
+    }
+
+    /// Define a formals list to some values in the current scope, checking
+    /// their number like a lambda would.
+    fn bind_values(&mut self, formals: SExp, vals: SExp) -> std::result::Result<(), Error> {
+        let (params, rest) = parse_params(formals)?;
+        let mut vals = spread(vals);
+
+        let arity = match rest {
+            Some(_) => Arity::from((params.len(),)),
+            None => Arity::from(params.len()),
+        };
+        arity.check(vals.len()?)?;
+
+        for p in params {
+            let (v, tail) = vals.split_car()?;
+            self.define(&p, v);
+            vals = tail;
+        }
+        if let Some(r) = rest {
+            self.define(&r, vals);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use super::super::super::SExp::{Atom, Null};
+use super::super::super::{Error, Primitive, Result, SExp};
+use super::super::Context;
+
+impl Context {
+    /// `(dynamic-wind before thunk after)`: call `thunk`, calling `before`
+    /// whenever its extent is entered and `after` whenever it is left, be it
+    /// by returning, by an error or by a continuation.
+    pub(super) fn dynamic_wind(&mut self, expr: SExp) -> Result {
+        self.eval_list(expr, |c, args| {
+            let (before, rest) = args.split_car()?;
+            let (thunk, rest) = rest.split_car()?;
+            let after = rest.car()?;
+
+            for proc in &[&before, &thunk, &after] {
+                if !matches!(proc, Atom(Primitive::Procedure(_))) {
+                    return Err(Error::Type {
+                        expected: "procedure",
+                        given: proc.type_of().to_string(),
+                    });
+                }
+            }
+
+            let (inner, outer) = (before.clone(), after.clone());
+            Ok(c.eval_then_values(Null.cons(before.clone()), move |c, _| {
+                // once the thunk returns, leave the extent before passing on
+                // its value
+                let after = after.clone();
+                c.push_then_values(move |c, val| {
+                    Ok(c.eval_then_values(Null.cons(after.clone()), move |_, _| Ok(val.clone())))
+                });
+                c.install_winder(inner.clone(), outer.clone());
+                Ok(c.defer(Null.cons(thunk.clone())))
+            }))
+        })
+    }
+}
@@ -1,5 +1,7 @@
-use super::super::proc::utils::{make_binary_numeric, make_unary_numeric};
-use super::super::Num;
+use super::super::proc::utils::{make_binary_expr, make_binary_numeric, make_unary_numeric};
+use super::super::Primitive::Number;
+use super::super::SExp::{self, Atom};
+use super::super::{Error, Num};
 use super::Context;
 
 macro_rules! define_with {
@@ -8,6 +10,24 @@ macro_rules! define_with {
     };
 }
 
+/// Lift a function that is only defined on real numbers to a procedure
+/// that rejects other arguments.
+fn real_binary(f: fn(Num, Num) -> Num) -> impl Fn(SExp, SExp) -> Result<SExp, Error> {
+    move |arg0, arg1| match (arg0, arg1) {
+        (Atom(Number(n0)), Atom(Number(n1))) if n0.is_real() && n1.is_real() => {
+            Ok(f(n0, n1).into())
+        }
+        (Atom(Number(n0)), e) if n0.is_real() => Err(Error::Type {
+            expected: "real number",
+            given: e.to_string(),
+        }),
+        (e, _) => Err(Error::Type {
+            expected: "real number",
+            given: e.to_string(),
+        }),
+    }
+}
+
 impl Context {
     /// Math functions that are less commonly used. Intended to be layered on top of the base context.
     ///
@@ -25,6 +45,15 @@ impl Context {
     /// asrt("(hypot 3 4)", "5");
     /// asrt("(recip 100)", "0.01");
     /// asrt("(log (exp 7))", "7");
+    /// asrt("(sqrt -4)", "+2i");
+    /// asrt("(log -1)", "(make-rectangular 0 pi)");
+    /// asrt("(sqrt 3+4i)", "2+i");
+    /// asrt("(sin 0+i)", "+1.1752011936438014i");
+    /// asrt("(asin 2)", "1.5707963267948966-1.3169578969248166i");
+    /// asrt("(acos 2)", "+1.3169578969248166i");
+    /// asrt("(atan 1+i)", "1.0172219678978514+0.4023594781085251i");
+    /// asrt("(log-10 -10)", "1+1.3643763538418412i");
+    /// assert!(ctx.run("(atan2 1 0+i)").is_err());
     /// ```
     #[must_use]
     pub fn math(mut self) -> Self {
@@ -65,14 +94,14 @@ impl Context {
         define_with!(self, "log-n", Num::log, make_binary_numeric);
 
         // trigonometry
-        define_with!(self, "hypot", Num::hypot, make_binary_numeric);
+        define_with!(self, "hypot", real_binary(Num::hypot), make_binary_expr);
         define_with!(self, "sin", Num::sin, make_unary_numeric);
         define_with!(self, "cos", Num::cos, make_unary_numeric);
         define_with!(self, "tan", Num::tan, make_unary_numeric);
         define_with!(self, "asin", Num::asin, make_unary_numeric);
         define_with!(self, "acos", Num::acos, make_unary_numeric);
         define_with!(self, "atan", Num::atan, make_unary_numeric);
-        define_with!(self, "atan2", Num::atan2, make_binary_numeric);
+        define_with!(self, "atan2", real_binary(Num::atan2), make_binary_expr);
 
         // unit conversions
         define_with!(self, "to-degrees", Num::to_degrees, make_unary_numeric);
@@ -1,8 +1,10 @@
-use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
 
-use super::{Cont, Env, Ns, Primitive, Proc, Result, SExp};
+use super::cont::{Cont, Dynamic, Frame, Handler, Link, ParamBinding, Winder, Winders};
+use super::proc::{CtxFn, Parameter};
+use super::SExp::{Atom, Null, Pair};
+use super::{Env, Error, Func, Ns, Primitive, Proc, Result, SExp};
 
 mod base;
 mod core;
@@ -23,7 +25,9 @@ mod write;
 /// case keeps the other environments immutable once they have been initialized.
 pub struct Context {
     core: Ns,
-    cont: Rc<RefCell<Cont>>,
+    cont: Option<Link>,
+    envt: Rc<Env>,
+    dynamic: Dynamic,
     /// You can `insert` additional definitions here to make them available
     /// throughout the runtime. These definitions will not go out of scope
     /// automatically, but can be overridden (see [`get`](#method.get) for
@@ -36,7 +40,9 @@ impl Default for Context {
     fn default() -> Self {
         Self {
             core: Self::core(),
-            cont: Cont::default().into_rc(),
+            cont: None,
+            envt: Env::default().into_rc(),
+            dynamic: Dynamic::default(),
             lang: Ns::new(),
             out: None,
         }
@@ -48,7 +54,7 @@ impl Context {
     ///
     /// See [`Context::pop`](#method.pop) for a usage example.
     pub fn push(&mut self) {
-        self.cont.borrow_mut().push();
+        self.envt = Env::new(Some(self.envt.clone())).into_rc();
     }
 
     /// Remove the most recently added scope.
@@ -69,12 +75,12 @@ impl Context {
     /// assert_eq!(ctx.get("x"), None);
     /// ```
     pub fn pop(&mut self) {
-        self.cont.borrow_mut().pop();
+        self.envt = self.envt.parent().unwrap_or_default();
     }
 
     /// Create a new definition in the current scope.
     pub fn define(&mut self, key: &str, value: SExp) {
-        self.cont.borrow().env().define(key, value)
+        self.envt.define(key, value)
     }
 
     /// Get the definition for a symbol in the execution environment.
@@ -114,7 +120,7 @@ impl Context {
         }
 
         // then the environment stack
-        if let Some(exp) = self.cont.borrow().env().get(key) {
+        if let Some(exp) = self.envt.get(key) {
             return Some(exp);
         }
 
@@ -145,46 +151,145 @@ impl Context {
     /// assert_eq!(ctx.get("x"), Some(SExp::from("potato"))); // check that its value is now "potato"
     /// ```
     pub fn set(&mut self, key: &str, value: SExp) -> Result {
-        self.cont.borrow().env().set(key, value)
+        self.envt.set(key, value)
     }
 
-    /// Push a new partial continuation with an existing environment.
+    /// Switch to an existing environment.
     pub(super) fn use_env(&mut self, envt: Rc<Env>) {
-        self.cont.borrow_mut().set_env(envt);
+        self.envt = envt;
     }
 
     /// Push a new partial continuation onto the stack.
-    pub(super) fn push_cont(&mut self) {
-        self.cont = Cont::from(&self.cont).into_rc();
+    fn push_frame(&mut self, frame: Frame) {
+        self.cont = Some(
+            Cont::new(
+                frame,
+                self.envt.clone(),
+                self.dynamic.clone(),
+                self.cont.take(),
+            )
+            .into_rc(),
+        );
     }
 
-    /// Pop the most recent partial continuation off of the stack.
-    pub(super) fn pop_cont(&mut self) {
-        let new = self.cont.borrow().parent().unwrap_or_default();
-        self.cont = new;
+    /// Pop the most recent partial continuation off of the stack, restoring
+    /// the environment and dynamic state it was pushed with.
+    fn pop_frame(&mut self) -> Frame {
+        let link = self
+            .cont
+            .take()
+            .expect("Every evaluation should end with a `Halt` frame.");
+        let (frame, envt, dynamic, cont) = Cont::into_parts(link);
+        self.envt = envt;
+        self.dynamic = dynamic;
+        self.cont = cont;
+        frame
     }
 
-    fn eval_args(&mut self, args: SExp) -> Result {
-        args.into_iter().map(|a| self.eval(a)).collect()
+    /// Pass the next value produced by the evaluator to `then`.
+    ///
+    /// Whatever `then` returns is treated like the return value of a special
+    /// form: it may be a value, or a deferred expression to keep evaluating.
+    pub(super) fn push_then<F>(&mut self, then: F)
+    where
+        F: Fn(&mut Self, SExp) -> Result + 'static,
+    {
+        self.push_frame(Frame::Then(Rc::new(then)));
     }
 
-    pub(super) fn eval_defer(&mut self, body: &SExp) -> Result {
-        let mut result = Ok(SExp::Atom(Primitive::Undefined));
+    /// Like `push_then`, but `then` also accepts several values at once, as
+    /// returned by `values`. Other continuations only take a single value.
+    pub(super) fn push_then_values<F>(&mut self, then: F)
+    where
+        F: Fn(&mut Self, SExp) -> Result + 'static,
+    {
+        self.push_frame(Frame::Values(Rc::new(then)));
+    }
 
-        let mut i = body.iter().peekable();
+    /// Evaluate an expression without growing the Rust stack, then pass its
+    /// value to `then`. The result of this method should be returned directly
+    /// from the calling special form.
+    pub(super) fn eval_then<F>(&mut self, expr: SExp, then: F) -> SExp
+    where
+        F: Fn(&mut Self, SExp) -> Result + 'static,
+    {
+        self.push_then(then);
+        self.defer(expr)
+    }
 
-        while let Some(expr) = i.next() {
-            if i.peek().is_some() {
-                result = self.eval(expr.to_owned());
-            } else {
-                result = Ok(self.defer(expr.to_owned()))
-            }
+    /// Like `eval_then`, but the expression may return several values.
+    pub(super) fn eval_then_values<F>(&mut self, expr: SExp, then: F) -> SExp
+    where
+        F: Fn(&mut Self, SExp) -> Result + 'static,
+    {
+        self.push_then_values(then);
+        self.defer(expr)
+    }
+
+    /// Capture the current continuation as a procedure.
+    pub(super) fn continuation(&self) -> SExp {
+        let cont = self
+            .cont
+            .clone()
+            .expect("Continuations can only be captured during evaluation.");
+        SExp::from(Proc::new::<_, _, &str>(
+            Func::Cont(cont, self.dynamic.clone()),
+            (0,),
+            None,
+        ))
+    }
 
-            if result.is_err() {
-                break;
+    /// Evaluate each expression in a list, in order, then pass the list of
+    /// their values to `then`.
+    pub(super) fn eval_list<F>(&mut self, exprs: SExp, then: F) -> Result
+    where
+        F: Fn(&mut Self, SExp) -> Result + 'static,
+    {
+        self.eval_list_from(Vec::new(), exprs, Rc::new(then), false)
+    }
+
+    /// Like `eval_list`, but each expression may return several values.
+    pub(super) fn eval_list_values<F>(&mut self, exprs: SExp, then: F) -> Result
+    where
+        F: Fn(&mut Self, SExp) -> Result + 'static,
+    {
+        self.eval_list_from(Vec::new(), exprs, Rc::new(then), true)
+    }
+
+    fn eval_list_from(
+        &mut self,
+        done: Vec<SExp>,
+        rest: SExp,
+        then: Rc<CtxFn>,
+        multiple: bool,
+    ) -> Result {
+        if rest.is_empty() {
+            return then(self, done.into());
+        }
+
+        let (head, tail) = rest.split_car()?;
+        let next = move |c: &mut Self, val| {
+            let mut done = done.clone();
+            done.push(val);
+            c.eval_list_from(done, tail.clone(), then.clone(), multiple)
+        };
+        Ok(if multiple {
+            self.eval_then_values(head, next)
+        } else {
+            self.eval_then(head, next)
+        })
+    }
+
+    pub(super) fn eval_defer(&mut self, body: &SExp) -> SExp {
+        match body {
+            Pair(p) if !p.tail().is_empty() => {
+                let rest = p.cdr();
+                self.eval_then_values(p.car(), move |c, _| Ok(c.eval_defer(&rest)))
             }
+            Pair(p) => self.defer(p.car()),
+            Null => Atom(Primitive::Undefined),
+            atom @ Atom(_) => self.defer(atom.clone()),
         }
-        result
     }
 
     /// Run a code snippet in an existing `Context`.
@@ -232,68 +337,370 @@ impl Context {
     /// ctx.eval(exp1);
     /// assert_eq!(ctx.eval(exp2).unwrap(), SExp::from(10));
     /// ```
-    pub fn eval(&mut self, mut expr: SExp) -> Result {
-        use super::Error::{NotAProcedure, NullList, UndefinedSymbol};
-        use super::Func::Tail;
-        use super::Primitive::{Procedure, Symbol, Undefined};
-        use super::SExp::{Atom, Null, Pair};
-
-        self.push_cont();
-
-        let res = loop {
-            expr = match expr {
-                // cannot evaluate null
-                Null => break Err(NullList),
-                // check if symbol is defined
-                Atom(Symbol(sym)) => match self.get(&sym) {
-                    None | Some(Atom(Undefined)) => {
-                        break Err(UndefinedSymbol { sym });
+    pub fn eval(&mut self, expr: SExp) -> Result {
+        let (cont, envt, dynamic) = (self.cont.clone(), self.envt.clone(), self.dynamic.clone());
+
+        let halt = Cont::new(Frame::Halt, envt.clone(), dynamic.clone(), cont.clone()).into_rc();
+        self.cont = Some(halt.clone());
+        let res = self.exec(expr, &halt);
+
+        self.cont = cont;
+        self.envt = envt;
+        self.dynamic = dynamic;
+        res
+    }
+
+    /// Call a procedure value with already-evaluated arguments.
+    pub(super) fn call(&mut self, proc: SExp, args: SExp) -> Result {
+        single(self.eval(application(proc, args))?)
+    }
+
+    /// Evaluate the arguments of a `Func::Ctx` procedure, which get them
+    /// unevaluated.
+    pub(super) fn eval_args(&mut self, args: SExp) -> std::result::Result<Vec<SExp>, Error> {
+        args.into_iter().map(|e| single(self.eval(e)?)).collect()
+    }
+
+    fn exec(&mut self, expr: SExp, halt: &Link) -> Result {
+        let mut step = Step::Eval(expr);
+        // errors are left for enclosing evaluations to signal to their own
+        // handlers, so that no handler sees the same error twice
+        let entry = self.dynamic.clone();
+
+        loop {
+            let next = match step {
+                Step::Eval(expr) => self.step_eval(expr),
+                Step::Return(val) => match self.pop_frame() {
+                    Frame::Halt => return Ok(val),
+                    frame => self.step_return(frame, val),
+                },
+            };
+
+            step = match next {
+                Ok(s) => s,
+                Err(Error::Jump { cont, value }) => match self.resume(cont, value, halt) {
+                    Ok(s) => s,
+                    Err(err) => return self.unwind(&entry, err),
+                },
+                Err(err) => match &self.dynamic.handlers {
+                    Some(h) if !is_same(&self.dynamic.handlers, &entry.handlers) => {
+                        let handler = h.clone();
+                        self.signal(&handler, err)
                     }
-                    Some(exp) => exp,
+                    _ => return self.unwind(&entry, err),
                 },
-                // continue evaluation
-                Atom(Procedure(Proc {
-                    func: Tail { body, envt },
-                    ..
-                })) => {
-                    self.cont.borrow_mut().set_env(envt);
-                    expr = body.deref().to_owned();
-                    continue;
-                }
-                // cannot reduce further
-                Atom(_) => break Ok(expr),
-                // it's an application
-                Pair { head, tail } => {
-                    // evaluate the first element
-                    match self.eval(*head)? {
-                        // if it is indeed a procedure
-                        Atom(Procedure(p)) => {
-                            let args = if p.defer_eval() {
-                                *tail
-                            } else {
-                                self.eval_args(*tail)?
-                            };
-                            // then apply it
-                            p.apply(args, self)?
-                        }
-                        // otherwise complain
-                        proc => {
-                            break Err(NotAProcedure {
-                                exp: proc.to_string(),
-                            });
-                        }
+            };
+        }
+    }
+
+    /// Install an exception handler for the rest of the current evaluation.
+    /// The previous handlers are restored when the most recently pushed
+    /// frame returns.
+    pub(super) fn install_handler(&mut self, proc: SExp) {
+        self.dynamic.handlers = Some(Rc::new(Handler {
+            proc,
+            outer: self.dynamic.handlers.take(),
+        }));
+    }
+
+    /// Give a parameter object a value for the rest of the current
+    /// evaluation. Like handlers, the previous value is restored by the next
+    /// frame.
+    pub(super) fn bind_parameter(&mut self, param: Rc<Parameter>, value: SExp) {
+        self.dynamic.params = Some(Rc::new(ParamBinding {
+            param,
+            value,
+            outer: self.dynamic.params.take(),
+        }));
+    }
+
+    /// The value of a parameter object in the current dynamic extent.
+    pub(crate) fn parameter_value(&self, param: &Rc<Parameter>) -> SExp {
+        let mut binding = self.dynamic.params.as_ref();
+        while let Some(b) = binding {
+            if Rc::ptr_eq(&b.param, param) {
+                return b.value.clone();
+            }
+            binding = b.outer.as_ref();
+        }
+        param.init.clone()
+    }
+
+    /// Enter a `dynamic-wind` extent for the rest of the current evaluation.
+    /// Like handlers, the previous extent is restored by the next frame.
+    pub(super) fn install_winder(&mut self, before: SExp, after: SExp) {
+        let outer = self.dynamic.winders.take();
+        self.dynamic.winders = Some(Rc::new(Winder {
+            before,
+            after,
+            depth: depth(&outer) + 1,
+            outer,
+        }));
+    }
+
+    /// Run the `after` thunks of the extents being left, then the `before`
+    /// thunks of the extents being entered, to move to the `target` extent.
+    fn wind_to(&mut self, target: &Winders) -> std::result::Result<(), Error> {
+        let common = common_extent(&self.dynamic.winders, target);
+
+        while !is_same(&self.dynamic.winders, &common) {
+            let winder = self.dynamic.winders.take().expect("Extents are nested.");
+            self.dynamic.winders.clone_from(&winder.outer);
+            self.call(winder.after.clone(), Null)?;
+        }
+
+        let mut entering = Vec::new();
+        let mut next = target.clone();
+        while !is_same(&next, &common) {
+            let winder = next.take().expect("Extents are nested.");
+            next.clone_from(&winder.outer);
+            entering.push(winder);
+        }
+        for winder in entering.into_iter().rev() {
+            self.call(winder.before.clone(), Null)?;
+            self.dynamic.winders = Some(winder);
+        }
+
+        Ok(())
+    }
+
+    /// Leave any extents entered since `entry` before passing on an error.
+    fn unwind(&mut self, entry: &Dynamic, err: Error) -> Result {
+        self.wind_to(&entry.winders)?;
+        Err(err)
+    }
+
+    /// The innermost installed exception handler.
+    pub(super) fn current_handler(&self) -> Option<Rc<Handler>> {
+        self.dynamic.handlers.clone()
+    }
+
+    /// Call an exception handler on a condition, with the handlers that were
+    /// current when it was installed. If `continuable`, the handler's return
+    /// value is returned in place of the exception. Otherwise, returning
+    /// raises the condition again, to the next handler out.
+    pub(super) fn call_handler(&mut self, handler: &Handler, obj: SExp, continuable: bool) -> SExp {
+        if continuable {
+            self.push_then_values(|_, val| Ok(val));
+        }
+        self.dynamic.handlers.clone_from(&handler.outer);
+        if !continuable {
+            let raised = obj.clone();
+            self.push_then_values(move |_, _| {
+                Err(Error::Raised {
+                    value: Box::new(raised.clone()),
+                })
+            });
+        }
+
+        self.defer(application(handler.proc.clone(), Null.cons(obj)))
+    }
+
+    /// Pass an error to an exception handler as a condition object.
+    fn signal(&mut self, handler: &Handler, err: Error) -> Step {
+        // a failing form may have left scopes of its own behind
+        if let Some(cont) = &self.cont {
+            self.envt = cont.env();
+        }
+        let obj = err.into_condition();
+        Step::from(self.call_handler(handler, obj, false))
+    }
+
+    fn step_eval(&mut self, expr: SExp) -> StepResult {
+        use super::Error::{NullList, Unassigned, UndefinedSymbol};
+        use super::Primitive::{Procedure, Symbol, Undefined};
+
+        match expr {
+            // cannot evaluate null
+            Null => Err(NullList),
+            // check if symbol is defined
+            Atom(Symbol(sym)) => match self.get(&sym) {
+                None => Err(UndefinedSymbol { sym }),
+                Some(Atom(Undefined)) => Err(Unassigned { sym }),
+                Some(exp) => Ok(Step::Return(exp)),
+            },
+            // continue evaluation
+            Atom(Procedure(Proc {
+                func: Func::Tail { body, envt },
+                ..
+            })) => {
+                self.use_env(envt);
+                Ok(Step::Eval(body.deref().clone()))
+            }
+            // cannot reduce further
+            Atom(_) => Ok(Step::Return(expr)),
+            // it's an application - evaluate the first element
+            Pair(p) => {
+                let (head, tail) = p.into_parts();
+                self.push_frame(Frame::Operator(tail));
+                Ok(Step::Eval(head))
+            }
+        }
+    }
+
+    fn step_return(&mut self, frame: Frame, val: SExp) -> StepResult {
+        use super::Error::NotAProcedure;
+        use super::Primitive::Procedure;
+
+        match frame {
+            Frame::Halt => unreachable!("`Halt` frames are handled by the evaluation loop."),
+            Frame::Operator(args) => match single(val)? {
+                // if it is indeed a procedure
+                Atom(Procedure(p)) => {
+                    if p.defer_eval() || args.is_empty() {
+                        self.apply(&p, args)
+                    } else {
+                        self.next_operand(p, Vec::new(), args)
                     }
                 }
-            };
+                // otherwise complain
+                proc => Err(NotAProcedure {
+                    exp: proc.to_string(),
+                }),
+            },
+            Frame::Operand {
+                func,
+                mut done,
+                rest,
+            } => {
+                done.push(single(val)?);
 
-            // see if we need to evaluate again
-            match expr {
-                Atom(Procedure(ref p)) if p.is_tail() => continue,
-                _ => break Ok(expr),
+                if rest.is_empty() {
+                    self.apply(&func, done.into())
+                } else {
+                    self.next_operand(func, done, rest)
+                }
             }
+            Frame::Then(then) => Ok(then(self, single(val)?)?.into()),
+            Frame::Values(then) => Ok(then(self, val)?.into()),
+        }
+    }
+
+    fn next_operand(&mut self, func: Proc, done: Vec<SExp>, args: SExp) -> StepResult {
+        let (head, rest) = args.split_car()?;
+        self.push_frame(Frame::Operand { func, done, rest });
+        Ok(Step::Eval(head))
+    }
+
+    fn apply(&mut self, func: &Proc, args: SExp) -> StepResult {
+        Ok(func.apply(args, self)?.into())
+    }
+
+    /// Switch over to a captured continuation, if it returns to this
+    /// evaluation. Otherwise, keep unwinding until we find the one it does
+    /// return to (or reach the top level).
+    fn resume(&mut self, cont: Box<SExp>, value: Box<SExp>, halt: &Link) -> StepResult {
+        let (link, dynamic) = match &*cont {
+            Atom(Primitive::Procedure(Proc {
+                func: Func::Cont(link, dynamic),
+                ..
+            })) => (link.clone(), dynamic.clone()),
+            _ => unreachable!("Only continuations can be resumed."),
         };
 
-        self.pop_cont();
-        res
+        let target = Cont::iter(&link).find(|c| c.is_halt());
+        let is_ours = target.as_ref().is_some_and(|t| Rc::ptr_eq(t, halt));
+
+        // a nested evaluation (run by a builtin like `vector-map`) that has
+        // already returned can't be resumed, since its Rust caller is gone
+        if let Some(t) = &target {
+            let is_live = Cont::iter(halt).any(|c| Rc::ptr_eq(&c, t));
+            if !is_live && t.parent().is_some() {
+                return Err(Error::Expired {
+                    cont: cont.to_string(),
+                });
+            }
+        }
+
+        let is_top = halt
+            .parent()
+            .is_none_or(|p| Cont::iter(&p).all(|c| !c.is_halt()));
+
+        if is_ours || is_top {
+            self.wind_to(&dynamic.winders)?;
+            self.dynamic = dynamic;
+            self.cont = Some(link);
+            Ok(Step::Return(*value))
+        } else {
+            Err(Error::Jump { cont, value })
+        }
+    }
+}
+
+/// The next thing for the evaluator to do.
+enum Step {
+    Eval(SExp),
+    Return(SExp),
+}
+
+type StepResult = std::result::Result<Step, Error>;
+
+impl From<SExp> for Step {
+    // special forms can return deferred expressions, which still need work
+    fn from(exp: SExp) -> Self {
+        match exp {
+            Atom(Primitive::Procedure(ref p)) if p.is_tail() => Step::Eval(exp),
+            _ => Step::Return(exp),
+        }
+    }
+}
+
+/// An expression applying a procedure to already-evaluated arguments. The
+/// tail of an improper argument list is kept, so evaluating the expression
+/// fails just as it would for a call written out with a dot.
+pub(super) fn application(proc: SExp, args: SExp) -> SExp {
+    let mut args = args.into_iter();
+    let quoted = args
+        .by_ref()
+        .map(|arg| Null.cons(arg).cons(SExp::sym("quote")))
+        .collect::<Vec<_>>();
+
+    quoted
+        .into_iter()
+        .rev()
+        .fold(args.rest().clone(), SExp::cons)
+        .cons(proc)
+}
+
+#[allow(clippy::ref_option)]
+fn is_same<T>(t0: &Option<Rc<T>>, t1: &Option<Rc<T>>) -> bool {
+    match (t0, t1) {
+        (Some(t0), Some(t1)) => Rc::ptr_eq(t0, t1),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Check that a value isn't several values from `values`, which only some
+/// continuations accept.
+fn single(val: SExp) -> Result {
+    match val {
+        Atom(Primitive::Values(vals)) => Err(Error::Type {
+            expected: "single value",
+            given: format!("{} values", vals.len()),
+        }),
+        val => Ok(val),
+    }
+}
+
+fn depth(winders: &Winders) -> usize {
+    winders.as_ref().map_or(0, |w| w.depth)
+}
+
+/// The innermost extent that two extents are both inside.
+fn common_extent(w0: &Winders, w1: &Winders) -> Winders {
+    let (mut w0, mut w1) = (w0.clone(), w1.clone());
+
+    while depth(&w0) > depth(&w1) {
+        w0 = w0.and_then(|w| w.outer.clone());
     }
+    while depth(&w1) > depth(&w0) {
+        w1 = w1.and_then(|w| w.outer.clone());
+    }
+    while !is_same(&w0, &w1) {
+        w0 = w0.and_then(|w| w.outer.clone());
+        w1 = w1.and_then(|w| w.outer.clone());
+    }
+
+    w0
 }
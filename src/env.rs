@@ -54,6 +54,11 @@ impl Env {
         None
     }
 
+    /// Whether this frame, ignoring its parents, binds `key`.
+    pub fn binds(&self, key: &str) -> bool {
+        self.env.borrow().contains_key(key)
+    }
+
     pub fn define(&self, key: &str, val: SExp) {
         self.env.borrow_mut().insert(key.to_string(), val);
     }
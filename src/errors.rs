@@ -1,5 +1,6 @@
 use std::fmt;
 
+use super::primitives::{Condition, Primitive};
 use super::SExp;
 
 #[derive(Debug)]
@@ -11,9 +12,19 @@ pub enum SyntaxError {
         given: Option<char>,
     },
     InvalidCond(SExp),
+    NoMatchingRule(SExp),
+    InvalidTemplate(SExp),
     NotANumber(String),
     NotAPrimitive(String),
     NotAToken(String),
+    UnexpectedDot(String),
+    InvalidEscape(String),
+    NotAByte(SExp),
+    /// A record constructor spec named a field the record type doesn't have.
+    UnknownField {
+        record: String,
+        field: String,
+    },
 }
 
 impl fmt::Display for SyntaxError {
@@ -35,11 +46,19 @@ impl fmt::Display for SyntaxError {
                 expected, exp
             ),
             SyntaxError::InvalidCond(e) => write!(f, "Invalid `cond` clause: {}", e),
+            SyntaxError::NoMatchingRule(e) => write!(f, "No syntax rule matches {}", e),
+            SyntaxError::InvalidTemplate(e) => write!(f, "Invalid syntax template: {}", e),
             SyntaxError::NotANumber(s) => write!(f, "Could not parse as a number: {}", s),
             SyntaxError::NotAPrimitive(s) => {
                 write!(f, "Could not parse as a primitive value: {}", s)
             }
             SyntaxError::NotAToken(s) => write!(f, "Unrecognized token: {}", s),
+            SyntaxError::UnexpectedDot(s) => write!(f, "Unexpected `.` in expression {}", s),
+            SyntaxError::InvalidEscape(s) => write!(f, "Invalid escape sequence: \\{}", s),
+            SyntaxError::NotAByte(e) => write!(f, "Bytevector element is not a byte: {}", e),
+            SyntaxError::UnknownField { record, field } => {
+                write!(f, "Record type {} has no field {}", record, field)
+            }
         }
     }
 }
@@ -55,6 +74,11 @@ pub enum Error {
     UndefinedSymbol {
         sym: String,
     },
+    /// A variable was used after it was bound, but before it was given a
+    /// value (e.g. by `letrec`).
+    Unassigned {
+        sym: String,
+    },
     Arity {
         expected: usize,
         given: usize,
@@ -77,7 +101,26 @@ pub enum Error {
     Index {
         i: usize,
     },
+    /// An exact number was divided by exact zero.
+    DivideByZero {
+        op: &'static str,
+    },
     IO(String),
+    /// An object raised by Scheme code and not handled.
+    Raised {
+        value: Box<SExp>,
+    },
+    /// A continuation was invoked after the builtin procedure it was
+    /// captured in had already returned, so there is nothing to resume.
+    Expired {
+        cont: String,
+    },
+    /// A continuation was invoked from inside a nested evaluation; this
+    /// carries it back out to the evaluation that owns it.
+    Jump {
+        cont: Box<SExp>,
+        value: Box<SExp>,
+    },
 }
 
 impl ::std::error::Error for Error {}
@@ -90,6 +133,7 @@ impl fmt::Display for Error {
                 write!(f, "Type error: expected {}, got {}", expected, given)
             }
             Error::UndefinedSymbol { sym } => write!(f, "Undefined symbol: {}", sym),
+            Error::Unassigned { sym } => write!(f, "Unassigned variable: {}", sym),
             Error::Arity { expected, given } => write!(
                 f,
                 "Arity mismatch: expected {} parameters, got {}.",
@@ -109,11 +153,68 @@ impl fmt::Display for Error {
             Error::NullList => write!(f, "Expected a pair, got null."),
             Error::NotAProcedure { exp } => write!(f, "{} is not a procedure.", exp),
             Error::Index { i } => write!(f, "Tried to access invalid index: [{}]", i),
+            Error::DivideByZero { op } => write!(f, "Division by zero signalled by {}.", op),
             Error::IO(err) => write!(f, "I/O error: {}", err),
+            Error::Raised { value } => match &**value {
+                SExp::Atom(Primitive::Condition(c)) => f.write_str(&c.report_string()),
+                value => write!(f, "Uncaught exception: {:?}", value),
+            },
+            Error::Expired { cont } => write!(
+                f,
+                "Could not resume continuation {}: the procedure it was captured in has returned.",
+                cont
+            ),
+            Error::Jump { cont, .. } => write!(f, "Could not resume continuation {}", cont),
         }
     }
 }
 
+impl Error {
+    /// The object handed to Scheme exception handlers for this error: what
+    /// was raised, or a condition describing an error from the runtime.
+    pub(crate) fn into_condition(self) -> SExp {
+        let kind = match &self {
+            Error::Raised { .. } | Error::Expired { .. } | Error::Jump { .. } => "simple-error",
+            Error::Syntax(_) => "parse-error",
+            Error::Type { .. } | Error::NotAList { .. } | Error::NullList => "wrong-type-argument",
+            Error::UndefinedSymbol { .. } => "unbound-variable",
+            Error::Unassigned { .. } => "unassigned-variable",
+            Error::Arity { .. } | Error::ArityMin { .. } | Error::ArityMax { .. } => {
+                "wrong-number-of-arguments"
+            }
+            Error::NotAProcedure { .. } => "inapplicable-object",
+            Error::Index { .. } => "bad-range-argument",
+            Error::DivideByZero { .. } => "divide-by-zero",
+            Error::IO(_) => "file-error",
+        };
+
+        let (message, irritants) = match self {
+            Error::Raised { value } => return *value,
+            Error::Type { expected, given } => (
+                format!("Type error: expected {}, got", expected),
+                SExp::from((given,)),
+            ),
+            Error::UndefinedSymbol { sym } => (
+                "Undefined symbol:".to_string(),
+                SExp::from((SExp::sym(&sym),)),
+            ),
+            Error::Unassigned { sym } => (
+                "Unassigned variable:".to_string(),
+                SExp::from((SExp::sym(&sym),)),
+            ),
+            Error::Index { i } => (
+                "Tried to access invalid index:".to_string(),
+                SExp::from((i,)),
+            ),
+            err => (err.to_string(), SExp::Null),
+        };
+
+        SExp::from(Primitive::Condition(Condition::new(
+            kind, message, irritants,
+        )))
+    }
+}
+
 impl From<SyntaxError> for Error {
     fn from(e: SyntaxError) -> Self {
         Error::Syntax(e)
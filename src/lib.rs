@@ -38,16 +38,15 @@ mod primitives;
 mod proc;
 mod utils;
 
-use self::cont::Cont;
 pub use self::ctx::Context;
 use self::env::{Env, Ns};
 pub use self::errors::Error;
 use self::errors::SyntaxError;
-pub use self::primitives::Num;
 use self::primitives::Primitive;
+pub use self::primitives::{Bytevector, Num};
 pub use self::proc::utils as proc_utils;
 use self::proc::{Func, Proc};
-pub use self::sexp::SExp;
+pub use self::sexp::{Cons, SExp};
 
 /// A shorthand Result type.
 pub type Result = ::std::result::Result<SExp, Error>;
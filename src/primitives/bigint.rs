@@ -0,0 +1,442 @@
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss
+)]
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// An arbitrary-precision integer, for values that do not fit in `Num::Int`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    /// Magnitude in base 2^32, least significant digit first, with no
+    /// trailing zeros (so zero is empty).
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        Self {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The number of bits in the magnitude.
+    #[must_use]
+    pub fn bits(&self) -> u32 {
+        match self.digits.last() {
+            Some(top) => (self.digits.len() as u32 - 1) * 32 + (32 - top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    #[must_use]
+    pub fn abs(&self) -> Self {
+        Self::new(false, self.digits.clone())
+    }
+
+    /// The nearest `f64`, rounding ties to even.
+    #[must_use]
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.bits();
+        let magnitude = if bits <= 64 {
+            self.digits
+                .iter()
+                .rev()
+                .fold(0_u64, |acc, &d| acc << 32 | u64::from(d)) as f64
+        } else {
+            // the top 64 bits, plus a sticky bit for anything nonzero below
+            // them, round the same way as the whole magnitude
+            let shift = bits - 64;
+            let (word, bit) = ((shift / 32) as usize, shift % 32);
+            let top = self.digits[word..]
+                .iter()
+                .take(3)
+                .rev()
+                .fold(0_u128, |acc, &d| acc << 32 | u128::from(d));
+            let top = (top >> bit) as u64;
+            let low = self.digits[..word].iter().any(|&d| d != 0)
+                || self.digits[word] & ((1 << bit) - 1) != 0;
+
+            (top | u64::from(low)) as f64 * 2_f64.powi(shift.min(2048) as i32)
+        };
+
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    #[must_use]
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.digits.len() > 4 {
+            return None;
+        }
+
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0_u128, |acc, &d| acc << 32 | u128::from(d));
+
+        if self.negative {
+            0_i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    #[must_use]
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut acc = Self::from(1);
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = &acc * &base;
+            }
+
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        acc
+    }
+
+    /// The integer square root of a non-negative number, or `None` if it is
+    /// negative.
+    #[must_use]
+    pub fn isqrt(&self) -> Option<Self> {
+        if self.negative {
+            return None;
+        }
+        if self.is_zero() {
+            return Some(self.clone());
+        }
+
+        // Newton's method, starting above the root and stopping once the
+        // estimate stops shrinking
+        let two = Self::from(2);
+        let mut x = two.pow(self.bits().div_ceil(2));
+        loop {
+            let (q, _) = self.div_rem(&x)?;
+            let (y, _) = (&x + &q).div_rem(&two)?;
+            if y >= x {
+                return Some(x);
+            }
+            x = y;
+        }
+    }
+
+    /// Truncating division and remainder, or `None` when dividing by zero.
+    #[must_use]
+    pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        if let (Some(a), Some(b)) = (self.to_i128(), other.to_i128()) {
+            if let (Some(q), Some(r)) = (a.checked_div(b), a.checked_rem(b)) {
+                return Some((q.into(), r.into()));
+            }
+        }
+
+        let (q, r) = div_rem_digits(&self.digits, &other.digits);
+        Some((
+            Self::new(self.negative != other.negative, q),
+            Self::new(self.negative, r),
+        ))
+    }
+
+    #[must_use]
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.abs(), other.abs());
+
+        while let Some((_, r)) = a.div_rem(&b) {
+            a = b;
+            b = r;
+        }
+
+        a
+    }
+
+    /// Parse an optionally signed string of digits in the given radix.
+    #[must_use]
+    pub fn parse_radix(s: &str, radix: u32) -> Option<Self> {
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        if s.is_empty() {
+            return None;
+        }
+
+        let mut digits = Vec::new();
+        for c in s.chars() {
+            mul_add_small(&mut digits, radix, c.to_digit(radix)?);
+        }
+
+        Some(Self::new(negative, digits))
+    }
+
+    #[must_use]
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        let mut digits = self.digits.clone();
+        let mut chars = Vec::new();
+        while !digits.is_empty() {
+            let d = div_small(&mut digits, radix);
+            chars.extend(std::char::from_digit(d, radix));
+            while digits.last() == Some(&0) {
+                digits.pop();
+            }
+        }
+
+        if self.negative {
+            chars.push('-');
+        }
+
+        chars.iter().rev().collect()
+    }
+}
+
+fn cmp_digits(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() < b.len() { (b, a) } else { (a, b) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+
+    for (i, &d) in long.iter().enumerate() {
+        let sum = u64::from(d) + u64::from(short.get(i).copied().unwrap_or(0)) + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+
+    result.push(carry as u32);
+    result
+}
+
+/// Subtract `b` from `a`, which must be at least as large.
+fn sub_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for (i, &d) in a.iter().enumerate() {
+        let sub = u64::from(b.get(i).copied().unwrap_or(0)) + borrow;
+        let (diff, under) = u64::from(d).overflowing_sub(sub);
+        result.push(diff as u32);
+        borrow = u64::from(under);
+    }
+
+    result
+}
+
+fn mul_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+
+        for (j, &y) in b.iter().enumerate() {
+            let prod = u64::from(x) * u64::from(y) + u64::from(result[i + j]) + carry;
+            result[i + j] = prod as u32;
+            carry = prod >> 32;
+        }
+
+        result[i + b.len()] = carry as u32;
+    }
+
+    result
+}
+
+fn mul_add_small(digits: &mut Vec<u32>, m: u32, a: u32) {
+    let mut carry = u64::from(a);
+
+    for d in digits.iter_mut() {
+        let prod = u64::from(*d) * u64::from(m) + carry;
+        *d = prod as u32;
+        carry = prod >> 32;
+    }
+
+    if carry > 0 {
+        digits.push(carry as u32);
+    }
+}
+
+/// Divide in place by a single digit, returning the remainder.
+fn div_small(digits: &mut [u32], d: u32) -> u32 {
+    let mut rem = 0;
+
+    for digit in digits.iter_mut().rev() {
+        let cur = rem << 32 | u64::from(*digit);
+        *digit = (cur / u64::from(d)) as u32;
+        rem = cur % u64::from(d);
+    }
+
+    rem as u32
+}
+
+/// Schoolbook binary long division of magnitudes.
+fn div_rem_digits(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [d] = b {
+        let mut q = a.to_vec();
+        let r = div_small(&mut q, *d);
+        return (q, vec![r]);
+    }
+
+    let mut q = vec![0; a.len()];
+    let mut r: Vec<u32> = Vec::with_capacity(b.len() + 1);
+
+    for i in (0..a.len() * 32).rev() {
+        // r = r * 2 + next bit of a
+        mul_add_small(&mut r, 2, (a[i / 32] >> (i % 32)) & 1);
+
+        if cmp_digits(&r, b) != Ordering::Less {
+            r = sub_digits(&r, b);
+            while r.last() == Some(&0) {
+                r.pop();
+            }
+            q[i / 32] |= 1 << (i % 32);
+        }
+    }
+
+    (q, r)
+}
+
+impl From<i128> for BigInt {
+    fn from(i: i128) -> Self {
+        let mut magnitude = i.unsigned_abs();
+        let mut digits = Vec::with_capacity(4);
+
+        while magnitude > 0 {
+            digits.push(magnitude as u32);
+            magnitude >>= 32;
+        }
+
+        Self::new(i < 0, digits)
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_digits(&self.digits, &other.digits),
+            (true, true) => cmp_digits(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        if self.is_zero() {
+            return f.write_str("0");
+        }
+
+        let mut digits = self.digits.clone();
+        let mut chunks = Vec::new();
+        while !digits.is_empty() {
+            chunks.push(div_small(&mut digits, CHUNK));
+            while digits.last() == Some(&0) {
+                digits.pop();
+            }
+        }
+
+        if self.negative {
+            f.write_str("-")?;
+        }
+
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{first}")?;
+        }
+        for chunk in chunks {
+            write!(f, "{chunk:09}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: Self) -> Self::Output {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_digits(&self.digits, &other.digits));
+        }
+
+        match cmp_digits(&self.digits, &other.digits) {
+            Ordering::Less => BigInt::new(other.negative, sub_digits(&other.digits, &self.digits)),
+            _ => BigInt::new(self.negative, sub_digits(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: Self) -> Self::Output {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_digits(&self.digits, &other.digits),
+        )
+    }
+}
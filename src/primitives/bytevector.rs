@@ -0,0 +1,91 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+
+/// A mutable sequence of bytes. Clones share the same bytes, so changes are
+/// seen through every reference to the bytevector.
+///
+/// # Example
+/// ```
+/// use parsley::prelude::*;
+/// use parsley::Bytevector;
+/// use std::convert::TryFrom;
+///
+/// let mut ctx = Context::base();
+/// ctx.define("payload", Bytevector::from(&b"\x01\x02"[..]).into());
+///
+/// let reply = ctx.run("(bytevector-append payload (bytevector 3))").unwrap();
+/// assert_eq!(Vec::<u8>::try_from(reply).unwrap(), vec![1, 2, 3]);
+/// ```
+#[derive(Clone)]
+pub struct Bytevector(Rc<RefCell<Vec<u8>>>);
+
+impl Bytevector {
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(Rc::new(RefCell::new(bytes)))
+    }
+
+    /// Borrow the bytes.
+    ///
+    /// # Panics
+    /// Panics if the bytevector is being mutated.
+    #[must_use]
+    pub fn borrow(&self) -> Ref<'_, Vec<u8>> {
+        self.0.borrow()
+    }
+
+    /// Mutably borrow the bytes.
+    ///
+    /// # Panics
+    /// Panics if the bytevector is borrowed elsewhere.
+    #[must_use]
+    pub fn borrow_mut(&self) -> RefMut<'_, Vec<u8>> {
+        self.0.borrow_mut()
+    }
+
+    /// A copy of the bytes, independent of the bytevector.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// An address identifying the bytevector, shared by all of its clones.
+    pub(crate) fn id(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+
+    /// Whether two bytevectors are the same object, as opposed to merely
+    /// having equal contents.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl From<Vec<u8>> for Bytevector {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<&[u8]> for Bytevector {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes.to_vec())
+    }
+}
+
+impl PartialEq for Bytevector {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || *self.borrow() == *other.borrow()
+    }
+}
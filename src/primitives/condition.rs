@@ -0,0 +1,65 @@
+use std::fmt::{self, Write};
+use std::rc::Rc;
+
+use super::super::SExp;
+
+/// An error object, as created by `error` or by the runtime when it signals
+/// an error to Scheme code. Conditions are only equal to themselves.
+#[derive(Clone)]
+pub struct Condition(Rc<Info>);
+
+struct Info {
+    kind: &'static str,
+    message: String,
+    irritants: SExp,
+}
+
+impl Condition {
+    /// A condition of the given kind (e.g. `simple-error` or
+    /// `wrong-type-argument`), with a message and a list of irritants.
+    #[must_use]
+    pub fn new(kind: &'static str, message: String, irritants: SExp) -> Self {
+        Self(Rc::new(Info {
+            kind,
+            message,
+            irritants,
+        }))
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        self.0.kind
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    #[must_use]
+    pub fn irritants(&self) -> SExp {
+        self.0.irritants.clone()
+    }
+
+    /// The message followed by the irritants, as `write` would show them.
+    #[must_use]
+    pub fn report_string(&self) -> String {
+        let mut report = self.0.message.clone();
+        for irritant in self.0.irritants.iter() {
+            let _ = write!(report, " {:?}", irritant);
+        }
+        report
+    }
+}
+
+impl PartialEq for Condition {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#[condition {}]", self.0.kind)
+    }
+}
@@ -3,8 +3,9 @@ use std::string::String as CoreString;
 
 use super::{
     super::{utils, SyntaxError},
-    Num,
+    Bytevector, Num,
     Primitive::{self, Boolean, Character, Number, String, Symbol},
+    CHAR_NAMES,
 };
 
 impl FromStr for Primitive {
@@ -21,14 +22,16 @@ impl FromStr for Primitive {
             return Ok(Number(num));
         }
 
-        if s.len() == 3 && s.starts_with("#\\") {
-            return Ok(Character(s.chars().nth(2).unwrap()));
+        if let Some(name) = s.strip_prefix("#\\") {
+            return parse_char(name)
+                .map(Character)
+                .ok_or_else(|| SyntaxError::NotAPrimitive(s.to_string()));
         }
 
         if s.starts_with('"') && s.ends_with('"') {
             match utils::find_closing_delim(s.chars(), '"', '"') {
-                Some(idx) if idx + 1 == s.len() => {
-                    return Ok(String(s.get(1..idx).unwrap().to_string()));
+                Some(idx) if idx + 1 == s.chars().count() => {
+                    return Ok(String(s[1..s.len() - 1].into()));
                 }
                 _ => (),
             }
@@ -42,6 +45,23 @@ impl FromStr for Primitive {
     }
 }
 
+/// Parse the part of a character literal after the `#\\`: a single
+/// character, a name from `CHAR_NAMES`, or a hex scalar value like `x41`.
+fn parse_char(name: &str) -> Option<char> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(c);
+    }
+
+    if let Some((_, c)) = CHAR_NAMES.iter().find(|(n, _)| *n == name) {
+        return Some(*c);
+    }
+
+    name.strip_prefix('x')
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .and_then(std::char::from_u32)
+}
+
 impl From<bool> for Primitive {
     fn from(b: bool) -> Self {
         Boolean(b)
@@ -57,6 +77,12 @@ where
     }
 }
 
+impl From<Bytevector> for Primitive {
+    fn from(b: Bytevector) -> Self {
+        Primitive::Bytevector(b)
+    }
+}
+
 impl From<char> for Primitive {
     fn from(c: char) -> Self {
         Character(c)
@@ -65,12 +91,12 @@ impl From<char> for Primitive {
 
 impl From<&str> for Primitive {
     fn from(s: &str) -> Self {
-        String(s.to_string())
+        String(s.into())
     }
 }
 
 impl From<CoreString> for Primitive {
     fn from(s: CoreString) -> Self {
-        String(s)
+        String(s.into())
     }
 }
@@ -4,13 +4,44 @@ use std::string::String as CoreString;
 use super::{proc::Proc, Ns, SExp};
 
 use self::Primitive::{
-    Boolean, Character, Env, Number, Procedure, String, Symbol, Undefined, Vector, Void,
+    Boolean, Character, Env, HashTable, Number, Procedure, String, Symbol, Undefined, Vector, Void,
 };
 
+pub use self::bytevector::Bytevector;
+pub use self::condition::Condition;
 pub use self::num::Num;
+pub use self::promise::{Promise, State as PromiseState};
+pub use self::record::{Record, RecordType};
+pub use self::string::SharedString;
+pub use self::table::HashTable as Table;
+pub use self::vector::SharedVec;
 
+mod bigint;
+mod bytevector;
+mod condition;
 mod from;
 mod num;
+mod promise;
+mod record;
+mod string;
+mod table;
+mod vector;
+
+/// Names accepted after `#\\` in character literals. The first name listed
+/// for a character is the one it is written with.
+const CHAR_NAMES: [(&str, char); 11] = [
+    ("alarm", '\u{7}'),
+    ("backspace", '\u{8}'),
+    ("delete", '\u{7f}'),
+    ("escape", '\u{1b}'),
+    ("newline", '\n'),
+    ("null", '\0'),
+    ("nul", '\0'),
+    ("return", '\r'),
+    ("space", ' '),
+    ("tab", '\t'),
+    ("linefeed", '\n'),
+];
 
 #[derive(Clone, PartialEq)]
 pub enum Primitive {
@@ -19,11 +50,31 @@ pub enum Primitive {
     Boolean(bool),
     Character(char),
     Number(Num),
-    String(CoreString),
+    String(SharedString),
     Symbol(CoreString),
     Env(Ns),
     Procedure(Proc),
-    Vector(Vec<SExp>),
+    Vector(SharedVec),
+    Bytevector(Bytevector),
+    Record(Record),
+    RecordType(RecordType),
+    Condition(Condition),
+    HashTable(Table),
+    Promise(Promise),
+    /// Several values returned at once by `values`. A single value is never
+    /// wrapped.
+    Values(Vec<SExp>),
+}
+
+fn fmt_bytes(b: &Bytevector, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("#u8(")?;
+    for (i, byte) in b.borrow().iter().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        write!(f, "{}", byte)?;
+    }
+    f.write_str(")")
 }
 
 impl fmt::Debug for Primitive {
@@ -32,20 +83,53 @@ impl fmt::Debug for Primitive {
             Void => f.write_str("#<void>"),
             Undefined => f.write_str("#<undefined>"),
             Boolean(b) => f.write_str(if *b { "#t" } else { "#f" }),
-            Character(c) => write!(f, "#\\{}", c),
+            Character(c) => match CHAR_NAMES.iter().find(|(_, ch)| ch == c) {
+                Some((name, _)) => write!(f, "#\\{}", name),
+                None if c.is_control() => write!(f, "#\\x{:x}", u32::from(*c)),
+                None => write!(f, "#\\{}", c),
+            },
             Number(n) => write!(f, "{}", n),
-            String(s) => write!(f, "\"{}\"", s),
+            String(s) => {
+                f.write_str("\"")?;
+                for c in s.borrow().chars() {
+                    match c {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '\n' => f.write_str("\\n")?,
+                        '\t' => f.write_str("\\t")?,
+                        '\r' => f.write_str("\\r")?,
+                        c if c.is_control() => write!(f, "\\x{:x};", u32::from(c))?,
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                f.write_str("\"")
+            }
             Symbol(s) => write!(f, "{}", s),
             Env(_) => write!(f, "#<environment>"),
+            HashTable(t) => write!(f, "#<hash-table {}>", t.len()),
+            Primitive::Promise(_) => f.write_str("#<promise>"),
             Procedure(p) => write!(f, "{}", p),
             Vector(v) => write!(
                 f,
                 "#({})",
-                v.iter()
+                v.borrow()
+                    .iter()
                     .map(|e| format!("{:?}", e))
                     .collect::<Vec<_>>()
                     .join(" ")
             ),
+            Primitive::Bytevector(b) => fmt_bytes(b, f),
+            Primitive::Record(r) => write!(f, "{}", r),
+            Primitive::RecordType(t) => write!(f, "{}", t),
+            Primitive::Condition(c) => write!(f, "{}", c),
+            Primitive::Values(vals) => write!(
+                f,
+                "{}",
+                vals.iter()
+                    .map(|e| format!("{:?}", e))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
         }
     }
 }
@@ -57,13 +141,31 @@ impl fmt::Display for Primitive {
             Boolean(b) => f.write_str(if *b { "#t" } else { "#f" }),
             Character(c) => write!(f, "{}", c),
             Number(n) => write!(f, "{}", n),
-            String(s) | Symbol(s) => f.write_str(s),
+            String(s) => f.write_str(&s.borrow()),
+            Symbol(s) => f.write_str(s),
             Env(_) => write!(f, "#<environment>"),
+            HashTable(t) => write!(f, "#<hash-table {}>", t.len()),
+            Primitive::Promise(_) => f.write_str("#<promise>"),
             Procedure(p) => write!(f, "{}", p),
             Vector(v) => write!(
                 f,
                 "#({})",
-                v.iter().map(SExp::to_string).collect::<Vec<_>>().join(" ")
+                v.borrow()
+                    .iter()
+                    .map(SExp::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            Primitive::Bytevector(b) => fmt_bytes(b, f),
+            Primitive::Record(r) => write!(f, "{}", r),
+            Primitive::RecordType(t) => write!(f, "{}", t),
+            Primitive::Condition(c) => write!(f, "{}", c),
+            Primitive::Values(vals) => f.write_str(
+                &vals
+                    .iter()
+                    .map(SExp::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
             ),
         }
     }
@@ -82,6 +184,13 @@ impl Primitive {
             Env(_) => "environment",
             Procedure { .. } => "procedure",
             Vector(_) => "vector",
+            Primitive::Bytevector(_) => "bytevector",
+            Primitive::Record(r) => r.record_type().name(),
+            Primitive::RecordType(_) => "record-type",
+            Primitive::Condition(_) => "condition",
+            HashTable(_) => "hash-table",
+            Primitive::Promise(_) => "promise",
+            Primitive::Values(_) => "values",
         }
     }
 }
@@ -5,24 +5,437 @@
     clippy::cast_sign_loss
 )]
 
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::f64::consts::{FRAC_PI_2, LN_10, LN_2, PI};
 use std::f64::{EPSILON, INFINITY, NEG_INFINITY};
 use std::fmt;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 use std::str::FromStr;
 
-use self::Num::{Float, Int};
+use self::Num::{Big, Complex, Float, Int, Rational};
 use super::super::SyntaxError;
+use super::bigint::BigInt;
 
 type IntT = isize;
+type Parts = (BigInt, BigInt);
+
+/// The precision of an `f64`'s significand.
+const FLOAT_BITS: u32 = 53;
 
 /// A numeric type that adapts its precision based on its usage.
-#[derive(Clone, Copy, Debug, PartialOrd)]
+///
+/// Integers and ratios of integers are exact, and grow as large as they need
+/// to. Complex numbers are always inexact: a literal like `3+4i` is read with
+/// floating point parts (and prints as `3.0+4.0i`), results like `(sqrt -4)`
+/// are inexact, and they have no exact counterpart.
+#[derive(Clone, Debug)]
 pub enum Num {
     Float(f64),
     Int(IntT),
+    /// An integer too large to fit in an `Int`.
+    Big(BigInt),
+    /// A ratio in lowest terms, with a denominator greater than one.
+    Rational(BigInt, BigInt),
+    /// Real and imaginary parts, where the imaginary part is never zero.
+    Complex(f64, f64),
+}
+
+fn one() -> BigInt {
+    BigInt::from(1)
+}
+
+/// Build an exact number in lowest terms, unless the denominator is zero.
+fn ratio(n: &BigInt, d: &BigInt) -> Option<Num> {
+    if d.is_zero() {
+        return None;
+    }
+
+    if *d == one() {
+        return Some(n.clone().into());
+    }
+
+    let mut g = n.gcd(d);
+    if d.is_negative() {
+        g = -&g;
+    }
+    let (n, d) = (n.div_rem(&g)?.0, d.div_rem(&g)?.0);
+
+    if d == one() {
+        return Some(n.into());
+    }
+
+    Some(Rational(n, d))
+}
+
+/// The nearest float to a ratio, even if its parts are too large to be
+/// floats themselves.
+fn ratio_to_f64(n: &BigInt, d: &BigInt) -> f64 {
+    // both parts are exact as floats, so one division rounds correctly
+    if n.bits() <= FLOAT_BITS && d.bits() <= FLOAT_BITS {
+        return n.to_f64() / d.to_f64();
+    }
+
+    // otherwise divide as integers, keeping a few more bits than a float
+    // has, and a sticky bit in case the division wasn't exact
+    let scale = (FLOAT_BITS + 2 + d.bits()).saturating_sub(n.bits()) + 1;
+    let shifted = &n.abs() * &BigInt::from(2).pow(scale);
+    let magnitude = shifted.div_rem(d).map_or(f64::NAN, |(q, r)| {
+        let sticky = BigInt::from(i128::from(!r.is_zero()));
+        let q = &(&q * &BigInt::from(2)) + &sticky;
+
+        // scaled down in two steps, so that tiny results can be subnormal
+        let scale = scale as i32 + 1;
+        q.to_f64() * 2_f64.powi(-scale / 2) * 2_f64.powi(scale / 2 - scale)
+    });
+
+    if n.is_negative() {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+fn is_even(n: &BigInt) -> bool {
+    n.div_rem(&BigInt::from(2)).is_none_or(|(_, r)| r.is_zero())
+}
+
+/// Flooring division of exact integers, for a positive divisor.
+fn floor_div(n: &BigInt, d: &BigInt) -> BigInt {
+    match n.div_rem(d) {
+        Some((q, r)) if r.is_negative() => &q - &one(),
+        Some((q, _)) => q,
+        None => BigInt::from(0),
+    }
+}
+
+/// The remainder of exact division, with the sign of the dividend.
+fn exact_rem((n0, d0): Parts, (n1, d1): Parts) -> Option<Parts> {
+    // n0/d0 - (n1/d1 * trunc((n0/d0) / (n1/d1)))
+    let (q, _) = (&n0 * &d1).div_rem(&(&d0 * &n1))?;
+    Some((&(&n0 * &d1) - &(&(&q * &n1) * &d0), &d0 * &d1))
+}
+
+/// Build a complex number, or a real one if there is no imaginary part.
+fn complex((re, im): (f64, f64)) -> Num {
+    if im == 0. {
+        Float(re)
+    } else {
+        Complex(re, im)
+    }
+}
+
+fn complex_mul((a, b): (f64, f64), (c, d): (f64, f64)) -> (f64, f64) {
+    (a * c - b * d, a * d + b * c)
+}
+
+fn complex_div((a, b): (f64, f64), (c, d): (f64, f64)) -> (f64, f64) {
+    let denom = c * c + d * d;
+    ((a * c + b * d) / denom, (b * c - a * d) / denom)
+}
+
+fn complex_pow(base: Num, exp: Num) -> Num {
+    (base.ln() * exp).exp()
+}
+
+fn complex_log(n: Num, base: Num) -> Num {
+    n.ln() / base.ln()
 }
 
 impl Num {
+    /// The numerator and denominator of an exact number.
+    fn parts(&self) -> Option<Parts> {
+        match self {
+            Int(i) => Some((BigInt::from(*i as i128), one())),
+            Big(b) => Some((b.clone(), one())),
+            Rational(n, d) => Some((n.clone(), d.clone())),
+            Float(_) | Complex(..) => None,
+        }
+    }
+
+    fn to_f64(&self) -> f64 {
+        match self {
+            Float(f) => *f,
+            Int(i) => *i as f64,
+            Big(b) => b.to_f64(),
+            Rational(n, d) => ratio_to_f64(n, d),
+            Complex(..) => f64::NAN,
+        }
+    }
+
+    fn to_complex(&self) -> (f64, f64) {
+        match self {
+            Complex(re, im) => (*re, *im),
+            _ => (self.to_f64(), 0.),
+        }
+    }
+
+    /// Apply an operation to the complex values of two numbers.
+    fn combine_complex(
+        &self,
+        other: &Self,
+        op: impl Fn((f64, f64), (f64, f64)) -> (f64, f64),
+    ) -> Self {
+        complex(op(self.to_complex(), other.to_complex()))
+    }
+
+    /// Apply an operation exactly if both operands are exact, otherwise (or
+    /// if the exact result would overflow) apply it to their float values.
+    fn combine(
+        &self,
+        other: &Self,
+        exact: impl Fn(Parts, Parts) -> Option<Parts>,
+        inexact: impl Fn(f64, f64) -> f64,
+    ) -> Self {
+        if let (Some(p0), Some(p1)) = (self.parts(), other.parts()) {
+            if let Some(n) = exact(p0, p1).and_then(|(n, d)| ratio(&n, &d)) {
+                return n;
+            }
+        }
+
+        Float(inexact(self.to_f64(), other.to_f64()))
+    }
+
+    /// Equivalence in the sense of `eqv?`: exact numbers must be equal, and
+    /// inexact ones must have the same representation, so `0.0` and `-0.0`
+    /// are different.
+    #[must_use]
+    pub fn eqv(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Float(f0), Float(f1)) => f0.to_bits() == f1.to_bits(),
+            (Complex(r0, i0), Complex(r1, i1)) => {
+                r0.to_bits() == r1.to_bits() && i0.to_bits() == i1.to_bits()
+            }
+            _ => self.is_exact() && other.is_exact() && self == other,
+        }
+    }
+
+    #[must_use]
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, Float(_) | Complex(..))
+    }
+
+    #[must_use]
+    pub fn is_real(&self) -> bool {
+        !matches!(self, Complex(..))
+    }
+
+    #[must_use]
+    pub fn is_rational(&self) -> bool {
+        match self {
+            Float(f) => f.is_finite(),
+            Complex(..) => false,
+            _ => true,
+        }
+    }
+
+    #[must_use]
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Float(f) => f.is_finite() && f.fract() == 0.,
+            Int(_) | Big(_) => true,
+            Rational(..) | Complex(..) => false,
+        }
+    }
+
+    /// Truncating integer division.
+    #[must_use]
+    pub fn quotient(self, other: Self) -> Self {
+        (self.clone() - self % other.clone()) / other
+    }
+
+    /// The remainder of flooring integer division, which has the sign of the
+    /// divisor.
+    #[must_use]
+    pub fn modulo(self, other: Self) -> Self {
+        let rem = self % other.clone();
+
+        if rem != Int(0) && (rem.to_f64() < 0.) != (other.to_f64() < 0.) {
+            rem + other
+        } else {
+            rem
+        }
+    }
+
+    #[must_use]
+    pub fn gcd(self, other: Self) -> Self {
+        let (mut a, mut b) = (self.abs(), other.abs());
+
+        while b != Int(0) {
+            let r = a % b.clone();
+            a = b;
+            b = r;
+        }
+
+        a
+    }
+
+    #[must_use]
+    pub fn lcm(self, other: Self) -> Self {
+        if self == Int(0) || other == Int(0) {
+            return self * other;
+        }
+
+        (self.clone() * other.clone()).abs() / self.gcd(other)
+    }
+
+    /// Parse an exact integer or ratio written in the given radix.
+    #[must_use]
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        if let Some(n) = BigInt::parse_radix(s, radix) {
+            return Some(n.into());
+        }
+
+        let (n, d) = s.split_once('/')?;
+        if d.starts_with(|c: char| c.is_digit(radix)) {
+            let (n, d) = (
+                BigInt::parse_radix(n, radix)?,
+                BigInt::parse_radix(d, radix)?,
+            );
+            return ratio(&n, &d);
+        }
+
+        None
+    }
+
+    /// Write a number in the given radix. Only exact numbers can be written
+    /// in radices other than ten.
+    #[must_use]
+    pub fn to_string_radix(&self, radix: u32) -> Option<String> {
+        let wide = |i: IntT| BigInt::from(i as i128).to_string_radix(radix);
+
+        match self {
+            _ if radix == 10 => Some(self.to_string()),
+            Int(i) => Some(wide(*i)),
+            Big(b) => Some(b.to_string_radix(radix)),
+            Rational(n, d) => Some(format!(
+                "{}/{}",
+                n.to_string_radix(radix),
+                d.to_string_radix(radix)
+            )),
+            Float(_) | Complex(..) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn make_rectangular<T>(self, imag: T) -> Self
+    where
+        Self: From<T>,
+    {
+        match Self::from(imag) {
+            Int(0) => self,
+            imag => complex((self.to_f64(), imag.to_f64())),
+        }
+    }
+
+    #[must_use]
+    pub fn make_polar<T>(self, angle: T) -> Self
+    where
+        Self: From<T>,
+    {
+        match Self::from(angle) {
+            Int(0) => self,
+            angle => {
+                let (m, a) = (self.to_f64(), angle.to_f64());
+                complex((m * a.cos(), m * a.sin()))
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn real_part(self) -> Self {
+        match self {
+            Complex(re, _) => Float(re),
+            _ => self,
+        }
+    }
+
+    #[must_use]
+    pub fn imag_part(self) -> Self {
+        match self {
+            Complex(_, im) => Float(im),
+            _ => Int(0),
+        }
+    }
+
+    #[must_use]
+    pub fn magnitude(self) -> Self {
+        match self {
+            Complex(re, im) => Float(re.hypot(im)),
+            _ => self.abs(),
+        }
+    }
+
+    #[must_use]
+    pub fn angle(self) -> Self {
+        match self {
+            Complex(re, im) => Float(im.atan2(re)),
+            Float(f) => Float(0_f64.atan2(f)),
+            _ if self.to_f64() < 0. => Float(PI),
+            _ => Int(0),
+        }
+    }
+
+    #[must_use]
+    pub fn numerator(self) -> Self {
+        match self {
+            Rational(n, _) => n.into(),
+            Float(f) => Float(f)
+                .to_exact()
+                .map_or(Float(f), |n| n.numerator().to_inexact()),
+            Int(_) | Big(_) | Complex(..) => self,
+        }
+    }
+
+    #[must_use]
+    pub fn denominator(self) -> Self {
+        match self {
+            Rational(_, d) => d.into(),
+            Float(f) => Float(f)
+                .to_exact()
+                .map_or(Float(f), |n| n.denominator().to_inexact()),
+            Int(_) | Big(_) => Int(1),
+            Complex(..) => Float(f64::NAN),
+        }
+    }
+
+    #[must_use]
+    pub fn to_inexact(self) -> Self {
+        match self {
+            Complex(..) => self,
+            _ => Float(self.to_f64()),
+        }
+    }
+
+    /// Convert to an exact number with the same value, if it is finite and
+    /// small enough.
+    #[must_use]
+    pub fn to_exact(&self) -> Option<Self> {
+        let f = match *self {
+            Float(f) if f.is_finite() => f,
+            Float(_) | Complex(..) => return None,
+            _ => return Some(self.clone()),
+        };
+
+        // every finite float is a binary fraction: mantissa * 2^exponent
+        let bits = f.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i32;
+        let mantissa = i128::from(if exponent == 0 {
+            (bits & 0x000f_ffff_ffff_ffff) << 1
+        } else {
+            (bits & 0x000f_ffff_ffff_ffff) | 0x0010_0000_0000_0000
+        });
+        let mantissa = BigInt::from(if f < 0. { -mantissa } else { mantissa });
+        let exponent = exponent - 1075;
+        let scale = BigInt::from(2).pow(exponent.unsigned_abs());
+
+        if exponent >= 0 {
+            ratio(&(&mantissa * &scale), &one())
+        } else {
+            ratio(&mantissa, &scale)
+        }
+    }
+
     #[must_use]
     pub fn abs(self) -> Self {
         match self {
@@ -31,9 +444,12 @@ impl Num {
                 if let Some(i0) = i.checked_abs() {
                     Int(i0)
                 } else {
-                    Float((i as f64).abs())
+                    BigInt::from(i as i128).abs().into()
                 }
             }
+            Big(b) => b.abs().into(),
+            Complex(..) => self.magnitude(),
+            Rational(n, d) => Rational(n.abs(), d),
         }
     }
 
@@ -43,12 +459,66 @@ impl Num {
         Self: From<T>,
     {
         match (self, other.into()) {
-            (Int(i0), Int(i1)) => i0
-                .checked_pow(i1 as u32)
-                .map_or_else(|| Float((i0 as f64).powi(i1 as i32)), Int),
-            (Float(f), Int(i)) => Float(f.powi(i as i32)),
-            (Int(i), Float(f)) => Float((i as f64).powf(f)),
-            (Float(f0), Float(f1)) => Float(f0.powf(f1)),
+            (Int(i0), Int(i1)) if u32::try_from(i1).is_ok_and(|e| i0.checked_pow(e).is_some()) => {
+                Int(i0.pow(i1 as u32))
+            }
+            (base, exp) if base.is_exact() && exp.is_exact() && exp.is_integer() => {
+                base.exact_pow(&exp)
+            }
+            (Float(f), Int(i)) => match i32::try_from(i) {
+                Ok(i) => Float(f.powi(i)),
+                Err(_) => Float(f.powf(i as f64)),
+            },
+            // a negative base has complex roots
+            (base, exp) if !base.is_real() || !exp.is_real() || base.to_f64() < 0. => {
+                complex_pow(base, exp)
+            }
+            (base, exp) => Float(base.to_f64().powf(exp.to_f64())),
+        }
+    }
+
+    /// The magnitude of an exact integer exponent, if it is small enough to
+    /// raise numbers to exactly.
+    #[must_use]
+    pub fn exact_exponent(&self) -> Option<u32> {
+        if !self.is_integer() {
+            return None;
+        }
+
+        let (e, _) = self.parts()?;
+        match e.abs().to_i128().map(u32::try_from) {
+            Some(Ok(e)) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Raise an exact number to an exact integer power.
+    fn exact_pow(&self, exp: &Self) -> Self {
+        let negative = exp.clone().is_sign_negative();
+        let exact = exp.exact_exponent().and_then(|e| {
+            let (n, d) = self.parts()?;
+            let (n, d) = (n.pow(e), d.pow(e));
+            if negative {
+                ratio(&d, &n)
+            } else {
+                ratio(&n, &d)
+            }
+        });
+
+        match exact {
+            Some(n) => n,
+            // too large to compute exactly, unless the magnitude stays put
+            None if *self == Int(1) => Int(1),
+            None if *self == Int(-1) => {
+                let (e, _) = exp.parts().unwrap_or((one(), one()));
+                if is_even(&e) {
+                    Int(1)
+                } else {
+                    Int(-1)
+                }
+            }
+            None if *self == Int(0) && !negative => Int(0),
+            None => Float(self.to_f64().powf(exp.to_f64())),
         }
     }
 
@@ -56,6 +526,7 @@ impl Num {
     pub fn is_nan(self) -> bool {
         match self {
             Float(f) => f.is_nan(),
+            Complex(re, im) => re.is_nan() || im.is_nan(),
             _ => false,
         }
     }
@@ -64,6 +535,7 @@ impl Num {
     pub fn is_infinite(self) -> bool {
         match self {
             Float(f) => f.is_infinite(),
+            Complex(re, im) => re.is_infinite() || im.is_infinite(),
             _ => false,
         }
     }
@@ -72,7 +544,8 @@ impl Num {
     pub fn is_finite(self) -> bool {
         match self {
             Float(f) => f.is_finite(),
-            _ => false,
+            Complex(re, im) => re.is_finite() && im.is_finite(),
+            _ => true,
         }
     }
 
@@ -81,6 +554,9 @@ impl Num {
         match self {
             Float(f) => f.is_sign_positive(),
             Int(i) => i.is_positive(),
+            Big(b) => !b.is_negative(),
+            Rational(n, _) => !n.is_negative(),
+            Complex(..) => false,
         }
     }
 
@@ -89,38 +565,58 @@ impl Num {
         match self {
             Float(f) => f.is_sign_negative(),
             Int(i) => i.is_negative(),
+            Big(b) => b.is_negative(),
+            Rational(n, _) => n.is_negative(),
+            Complex(..) => false,
         }
     }
 
     #[must_use]
     pub fn floor(self) -> Self {
         match self {
-            Float(f) => Int(f.floor() as IntT),
-            _ => self,
+            Float(f) => Float(f.floor()),
+            Complex(re, im) => complex((re.floor(), im.floor())),
+            Rational(n, d) => floor_div(&n, &d).into(),
+            Int(_) | Big(_) => self,
         }
     }
 
     #[must_use]
     pub fn ceil(self) -> Self {
         match self {
-            Float(f) => Int(f.ceil() as IntT),
-            _ => self,
+            Float(f) => Float(f.ceil()),
+            Complex(re, im) => complex((re.ceil(), im.ceil())),
+            Rational(n, d) => (&floor_div(&n, &d) + &one()).into(),
+            Int(_) | Big(_) => self,
         }
     }
 
     #[must_use]
     pub fn round(self) -> Self {
         match self {
-            Float(f) => Int(f.round() as IntT),
-            _ => self,
+            Float(f) => Float(f.round_ties_even()),
+            Complex(re, im) => complex((re.round_ties_even(), im.round_ties_even())),
+            // ties go to the even neighbor
+            Rational(n, d) => {
+                let floor = floor_div(&n, &d);
+                let rem = &n - &(&floor * &d);
+                match (&rem + &rem).cmp(&d) {
+                    Ordering::Less => floor.into(),
+                    Ordering::Equal if is_even(&floor) => floor.into(),
+                    _ => (&floor + &one()).into(),
+                }
+            }
+            Int(_) | Big(_) => self,
         }
     }
 
     #[must_use]
     pub fn trunc(self) -> Self {
         match self {
-            Float(f) => Int(f.trunc() as IntT),
-            _ => self,
+            Float(f) => Float(f.trunc()),
+            Complex(re, im) => complex((re.trunc(), im.trunc())),
+            Rational(n, d) => n.div_rem(&d).map_or(Int(0), |(q, _)| q.into()),
+            Int(_) | Big(_) => self,
         }
     }
 
@@ -128,59 +624,103 @@ impl Num {
     pub fn fract(self) -> Self {
         match self {
             Float(f) => Float(f.fract()),
-            _ => Int(0),
+            Complex(re, im) => complex((re.fract(), im.fract())),
+            Rational(..) => self.clone() - self.trunc(),
+            Int(_) | Big(_) => Int(0),
         }
     }
 
     #[must_use]
     pub fn signum(self) -> Self {
         match self {
-            Float(f) => Int(f.signum() as IntT),
+            Float(f) => Float(f.signum()),
             Int(i) => Int(i.signum()),
+            Big(b) => Int(if b.is_negative() { -1 } else { 1 }),
+            Rational(n, _) => Int(if n.is_negative() { -1 } else { 1 }),
+            Complex(..) => self.clone() / self.magnitude(),
         }
     }
 
     #[must_use]
     pub fn recip(self) -> Self {
-        Float(f64::from(self).recip())
+        Int(1) / self
     }
 
     #[must_use]
     pub fn sqrt(self) -> Self {
-        Float(f64::from(self).sqrt())
+        if let Some(root) = self.exact_sqrt() {
+            return root;
+        }
+
+        match self {
+            Complex(..) => (self.ln() * 0.5).exp(),
+            _ if self.to_f64() < 0. => Complex(0., (-self.to_f64()).sqrt()),
+            _ => Float(self.to_f64().sqrt()),
+        }
+    }
+
+    /// The exact square root of a non-negative exact number whose numerator
+    /// and denominator are both perfect squares.
+    fn exact_sqrt(&self) -> Option<Self> {
+        let (n, d) = self.parts()?;
+        let root = |i: &BigInt| i.isqrt().filter(|r| &(r * r) == i);
+
+        ratio(&root(&n)?, &root(&d)?)
     }
 
     #[must_use]
     pub fn cbrt(self) -> Self {
-        Float(f64::from(self).cbrt())
+        match self {
+            Complex(..) => (self.ln() / 3.).exp(),
+            _ => Float(self.to_f64().cbrt()),
+        }
     }
 
     #[must_use]
     pub fn exp(self) -> Self {
-        Float(f64::from(self).exp())
+        match self {
+            Complex(re, im) => {
+                let m = re.exp();
+                complex((m * im.cos(), m * im.sin()))
+            }
+            _ => Float(self.to_f64().exp()),
+        }
     }
 
     #[must_use]
     pub fn ln(self) -> Self {
-        Float(f64::from(self).ln())
+        match self {
+            Complex(re, im) => complex((re.hypot(im).ln(), im.atan2(re))),
+            _ if self.to_f64() < 0. => Complex((-self.to_f64()).ln(), PI),
+            _ => Float(self.to_f64().ln()),
+        }
     }
 
     #[must_use]
     pub fn exp2(self) -> Self {
         match self {
-            Float(f) => Float(f.exp2()),
-            Int(i) => Int((2 as IntT).pow(i as u32)),
+            Int(_) => Int(2).pow(self),
+            Complex(..) => complex_pow(Int(2), self),
+            _ => Float(self.to_f64().exp2()),
         }
     }
 
     #[must_use]
     pub fn log2(self) -> Self {
-        Float(f64::from(self).log2())
+        if self.is_real() && self.to_f64() >= 0. {
+            Float(self.to_f64().log2())
+        } else {
+            self.ln() / LN_2
+        }
     }
 
     #[must_use]
     pub fn log10(self) -> Self {
-        Float(f64::from(self).log10())
+        if self.is_real() && self.to_f64() >= 0. {
+            Float(self.to_f64().log10())
+        } else {
+            self.ln() / LN_10
+        }
     }
 
     #[must_use]
@@ -188,7 +728,12 @@ impl Num {
     where
         Self: From<T>,
     {
-        Float(f64::from(self).log(f64::from(Self::from(other))))
+        match Self::from(other) {
+            base if self.is_real() && base.is_real() && self.to_f64() >= 0. => {
+                Float(self.to_f64().log(base.to_f64()))
+            }
+            base => complex_log(self, base),
+        }
     }
 
     #[must_use]
@@ -196,37 +741,63 @@ impl Num {
     where
         Self: From<T>,
     {
-        Float(f64::from(self).hypot(f64::from(Self::from(other))))
+        Float(self.to_f64().hypot(Self::from(other).to_f64()))
     }
 
     #[must_use]
     pub fn sin(self) -> Self {
-        Float(f64::from(self).sin())
+        match self {
+            Complex(re, im) => complex((re.sin() * im.cosh(), re.cos() * im.sinh())),
+            _ => Float(self.to_f64().sin()),
+        }
     }
 
     #[must_use]
     pub fn cos(self) -> Self {
-        Float(f64::from(self).cos())
+        match self {
+            Complex(re, im) => complex((re.cos() * im.cosh(), -(re.sin() * im.sinh()))),
+            _ => Float(self.to_f64().cos()),
+        }
     }
 
     #[must_use]
     pub fn tan(self) -> Self {
-        Float(f64::from(self).tan())
+        match self {
+            Complex(..) => self.clone().sin() / self.cos(),
+            _ => Float(self.to_f64().tan()),
+        }
     }
 
     #[must_use]
     pub fn asin(self) -> Self {
-        Float(f64::from(self).asin())
+        if self.is_real() && self.to_f64().abs() <= 1. {
+            return Float(self.to_f64().asin());
+        }
+
+        // -i ln(iz + sqrt(1 - z^2))
+        let root = (Int(1) - self.clone() * self.clone()).sqrt();
+        Complex(0., -1.) * (Complex(0., 1.) * self + root).ln()
     }
 
     #[must_use]
     pub fn acos(self) -> Self {
-        Float(f64::from(self).acos())
+        if self.is_real() && self.to_f64().abs() <= 1. {
+            return Float(self.to_f64().acos());
+        }
+
+        Float(FRAC_PI_2) - self.asin()
     }
 
     #[must_use]
     pub fn atan(self) -> Self {
-        Float(f64::from(self).atan())
+        match self {
+            // (ln(1 + iz) - ln(1 - iz)) / 2i
+            Complex(..) => {
+                let iz = Complex(0., 1.) * self;
+                ((Int(1) + iz.clone()).ln() - (Int(1) - iz).ln()) / Complex(0., 2.)
+            }
+            _ => Float(self.to_f64().atan()),
+        }
     }
 
     #[must_use]
@@ -234,17 +805,23 @@ impl Num {
     where
         Self: From<T>,
     {
-        Float(f64::from(self).atan2(f64::from(Self::from(other))))
+        Float(self.to_f64().atan2(Self::from(other).to_f64()))
     }
 
     #[must_use]
     pub fn to_degrees(self) -> Self {
-        Float(f64::from(self).to_degrees())
+        match self {
+            Complex(..) => self * (180. / PI),
+            _ => Float(self.to_f64().to_degrees()),
+        }
     }
 
     #[must_use]
     pub fn to_radians(self) -> Self {
-        Float(f64::from(self).to_radians())
+        match self {
+            Complex(..) => self * (PI / 180.),
+            _ => Float(self.to_f64().to_radians()),
+        }
     }
 }
 
@@ -256,12 +833,56 @@ impl FromStr for Num {
             return Ok(Int(num));
         }
 
+        match s {
+            "+inf.0" => return Ok(Float(f64::INFINITY)),
+            "-inf.0" => return Ok(Float(f64::NEG_INFINITY)),
+            "+nan.0" | "-nan.0" => return Ok(Float(f64::NAN)),
+            _ => (),
+        }
+
+        // big integers and ratios, like `1/3`
+        if let Some(num) = Self::from_str_radix(s, 10) {
+            return Ok(num);
+        }
+
         if let Ok(num) = s.parse::<f64>() {
             return Ok(Float(num));
         }
 
-        Err(SyntaxError::NotANumber(s.to_string()))
+        parse_complex(s).ok_or_else(|| SyntaxError::NotANumber(s.to_string()))
+    }
+}
+
+fn parse_real(s: &str) -> Option<Num> {
+    s.parse::<Num>().ok().filter(Num::is_real)
+}
+
+/// Parse rectangular (`3+4i`, `-i`) or polar (`1@3.14`) complex numbers.
+fn parse_complex(s: &str) -> Option<Num> {
+    if let Some((m, a)) = s.split_once('@') {
+        return Some(parse_real(m)?.make_polar(parse_real(a)?));
     }
+
+    let body = s.strip_suffix('i')?;
+    // the imaginary part starts at the last sign that is not an exponent's
+    let (split, _) = body
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| (c == '+' || c == '-') && !body[..i].ends_with(['e', 'E']))?;
+    let (re, im) = body.split_at(split);
+
+    let re = if re.is_empty() {
+        Int(0)
+    } else {
+        parse_real(re)?
+    };
+    let im = match im {
+        "+" => Int(1),
+        "-" => Int(-1),
+        _ => parse_real(im)?,
+    };
+
+    Some(re.make_rectangular(im))
 }
 
 impl From<IntT> for Num {
@@ -294,16 +915,47 @@ impl From<f64> for Num {
     }
 }
 
+impl From<BigInt> for Num {
+    fn from(n: BigInt) -> Self {
+        match n.to_i128().map(IntT::try_from) {
+            Some(Ok(i)) => Num::Int(i),
+            _ => Num::Big(n),
+        }
+    }
+}
+
 impl PartialEq for Num {
     fn eq(&self, other: &Self) -> bool {
-        match (*self, *other) {
+        match (self, other) {
             (Int(i0), Int(i1)) => i0 == i1,
-            (Float(f), Int(i)) | (Int(i), Float(f)) => (f - (i as f64)).abs() < EPSILON,
+            (Big(b0), Big(b1)) => b0 == b1,
+            (Rational(n0, d0), Rational(n1, d1)) => n0 == n1 && d0 == d1,
             (Float(f0), Float(f1)) => {
-                f0 == INFINITY && f1 == INFINITY
-                    || f0 == NEG_INFINITY && f1 == NEG_INFINITY
+                *f0 == INFINITY && *f1 == INFINITY
+                    || *f0 == NEG_INFINITY && *f1 == NEG_INFINITY
                     || (f0 - f1).abs() < EPSILON
             }
+            (Complex(r0, i0), Complex(r1, i1)) => {
+                Float(*r0) == Float(*r1) && Float(*i0) == Float(*i1)
+            }
+            (Float(f), n) | (n, Float(f)) => (f - n.to_f64()).abs() < EPSILON,
+            // exact numbers are normalized, so different kinds never match
+            _ => false,
+        }
+    }
+}
+
+impl PartialOrd for Num {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if let (Int(i0), Int(i1)) = (self, other) {
+            return i0.partial_cmp(i1);
+        }
+
+        match (self.parts(), other.parts()) {
+            // denominators are always positive
+            (Some((n0, d0)), Some((n1, d1))) => Some((&n0 * &d1).cmp(&(&n1 * &d0))),
+            _ if self == other => Some(Ordering::Equal),
+            _ => self.to_f64().partial_cmp(&other.to_f64()),
         }
     }
 }
@@ -313,24 +965,44 @@ impl From<Num> for usize {
         match n {
             Num::Float(f) => f as Self,
             Num::Int(i) => i as Self,
+            Num::Big(b) => b.to_f64() as Self,
+            Num::Complex(re, _) => re as Self,
+            Num::Rational(..) => usize::from(n.trunc()),
         }
     }
 }
 
 impl From<Num> for f64 {
     fn from(n: Num) -> Self {
-        match n {
-            Num::Float(f) => f,
-            Num::Int(i) => i as Self,
-        }
+        n.to_f64()
     }
 }
 
 impl fmt::Display for Num {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Float(l) => write!(f, "{}", l),
+            Float(l) => write!(f, "{}", Float64(*l)),
             Int(i) => write!(f, "{}", i),
+            Big(b) => write!(f, "{}", b),
+            Complex(re, im) if im.is_sign_negative() => {
+                write!(f, "{}{}i", Float64(*re), Float64(*im))
+            }
+            Complex(re, im) => write!(f, "{}+{}i", Float64(*re), Float64(*im)),
+            Rational(n, d) => write!(f, "{}/{}", n, d),
+        }
+    }
+}
+
+/// Displays floats with a decimal point, so they can be told apart from
+/// exact integers.
+struct Float64(f64);
+
+impl fmt::Display for Float64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_finite() && self.0.fract() == 0. {
+            write!(f, "{:.1}", self.0)
+        } else {
+            write!(f, "{}", self.0)
         }
     }
 }
@@ -342,9 +1014,12 @@ impl Neg for Num {
         match self {
             Int(i) => match i.checked_neg() {
                 Some(i0) => Int(i0),
-                None => Float(-(i as f64)),
+                None => (-&BigInt::from(i as i128)).into(),
             },
+            Big(b) => (-&b).into(),
             Float(f) => Float(-f),
+            Complex(re, im) => Complex(-re, -im),
+            Rational(n, d) => Rational(-&n, d),
         }
     }
 }
@@ -357,11 +1032,17 @@ where
 
     fn add(self, other: T) -> Self::Output {
         match (self, other.into()) {
+            (n0, n1) if !n0.is_real() || !n1.is_real() => {
+                n0.combine_complex(&n1, |(a, b), (c, d)| (a + c, b + d))
+            }
             (Int(i0), Int(i1)) => i0
                 .checked_add(i1)
-                .map_or_else(|| Float((i0 as f64) + (i1 as f64)), Int),
-            (Float(f), Int(i)) | (Int(i), Float(f)) => Float(f + (i as f64)),
-            (Float(f0), Float(f1)) => Float(f0 + f1),
+                .map_or_else(|| BigInt::from(i0 as i128 + i1 as i128).into(), Int),
+            (n0, n1) => n0.combine(
+                &n1,
+                |(n0, d0), (n1, d1)| Some((&(&n0 * &d1) + &(&n1 * &d0), &d0 * &d1)),
+                |f0, f1| f0 + f1,
+            ),
         }
     }
 }
@@ -374,12 +1055,17 @@ where
 
     fn sub(self, other: T) -> Self::Output {
         match (self, other.into()) {
+            (n0, n1) if !n0.is_real() || !n1.is_real() => {
+                n0.combine_complex(&n1, |(a, b), (c, d)| (a - c, b - d))
+            }
             (Int(i0), Int(i1)) => i0
                 .checked_sub(i1)
-                .map_or_else(|| Float((i0 as f64) - (i1 as f64)), Int),
-            (Float(f), Int(i)) => Float(f - (i as f64)),
-            (Int(i), Float(f)) => Float((i as f64) - f),
-            (Float(f0), Float(f1)) => Float(f0 - f1),
+                .map_or_else(|| BigInt::from(i0 as i128 - i1 as i128).into(), Int),
+            (n0, n1) => n0.combine(
+                &n1,
+                |(n0, d0), (n1, d1)| Some((&(&n0 * &d1) - &(&n1 * &d0), &d0 * &d1)),
+                |f0, f1| f0 - f1,
+            ),
         }
     }
 }
@@ -392,11 +1078,15 @@ where
 
     fn mul(self, other: T) -> Self::Output {
         match (self, other.into()) {
+            (n0, n1) if !n0.is_real() || !n1.is_real() => n0.combine_complex(&n1, complex_mul),
             (Int(i0), Int(i1)) => i0
                 .checked_mul(i1)
-                .map_or_else(|| Float((i0 as f64) * (i1 as f64)), Int),
-            (Float(f), Int(i)) | (Int(i), Float(f)) => Float(f * (i as f64)),
-            (Float(f0), Float(f1)) => Float(f0 * f1),
+                .map_or_else(|| BigInt::from(i0 as i128 * i1 as i128).into(), Int),
+            (n0, n1) => n0.combine(
+                &n1,
+                |(n0, d0), (n1, d1)| Some((&n0 * &n1, &d0 * &d1)),
+                |f0, f1| f0 * f1,
+            ),
         }
     }
 }
@@ -408,19 +1098,13 @@ where
     type Output = Self;
 
     fn div(self, other: T) -> Self::Output {
-        match (self, other.into()) {
-            (Int(i0), Int(i1)) => {
-                if let Some(0) = i0.checked_rem(i1) {
-                    if let Some(i) = i0.checked_div(i1) {
-                        return Int(i);
-                    }
-                }
-
-                Float((i0 as f64) / (i1 as f64))
-            }
-            (Float(f), Int(i)) => Float(f / (i as f64)),
-            (Int(i), Float(f)) => Float((i as f64) / f),
-            (Float(f0), Float(f1)) => Float(f0 / f1),
+        match (self, Self::from(other)) {
+            (n0, n1) if !n0.is_real() || !n1.is_real() => n0.combine_complex(&n1, complex_div),
+            (n0, n1) => n0.combine(
+                &n1,
+                |(n0, d0), (n1, d1)| Some((&n0 * &d1, &d0 * &n1)),
+                |f0, f1| f0 / f1,
+            ),
         }
     }
 }
@@ -433,13 +1117,8 @@ where
 
     fn rem(self, other: T) -> Self::Output {
         match (self, other.into()) {
-            (Int(i0), Int(i1)) => match i0.checked_rem(i1) {
-                Some(i) => Int(i),
-                None => Float((i0 as f64) % (i1 as f64)),
-            },
-            (Float(f), Int(i)) => Float(f % (i as f64)),
-            (Int(i), Float(f)) => Float((i as f64) % f),
-            (Float(f0), Float(f1)) => Float(f0 % f1),
+            (Int(i0), Int(i1)) if i0.checked_rem(i1).is_some() => Int(i0 % i1),
+            (n0, n1) => n0.combine(&n1, exact_rem, |f0, f1| f0 % f1),
         }
     }
 }
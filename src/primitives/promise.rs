@@ -0,0 +1,58 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::super::{Env, SExp};
+
+/// A promise made by `delay`, `delay-force` or `make-promise`, which
+/// remembers its value once it has been forced. Promises are only equal to
+/// themselves.
+#[derive(Clone)]
+pub struct Promise(Rc<RefCell<Rc<RefCell<State>>>>);
+
+#[derive(Clone)]
+pub enum State {
+    /// The value the promise was forced to.
+    Done(SExp),
+    /// An expression still to be evaluated, in the environment it was
+    /// delayed in. A `lazy` body evaluates to another promise, which this one
+    /// takes the place of.
+    Pending {
+        body: SExp,
+        envt: Rc<Env>,
+        lazy: bool,
+    },
+}
+
+impl Promise {
+    #[must_use]
+    pub fn new(state: State) -> Self {
+        Self(Rc::new(RefCell::new(Rc::new(RefCell::new(state)))))
+    }
+
+    #[must_use]
+    pub fn state(&self) -> State {
+        self.0.borrow().borrow().clone()
+    }
+
+    pub fn set_state(&self, state: State) {
+        *self.0.borrow().borrow_mut() = state;
+    }
+
+    /// Make this promise share its state with `other`, so that forcing
+    /// either of them settles both.
+    pub fn share(&self, other: &Self) {
+        let shared = other.0.borrow().clone();
+        *self.0.borrow_mut() = shared;
+    }
+
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl PartialEq for Promise {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
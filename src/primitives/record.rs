@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::super::SExp;
+
+/// Numbers records and record types as they're created, so they can be told
+/// apart when printed.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+fn next_id() -> usize {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A type created by `define-record-type`. Types are only equal to
+/// themselves, even if they have the same name and fields.
+#[derive(Clone)]
+pub struct RecordType(Rc<TypeInfo>);
+
+struct TypeInfo {
+    id: usize,
+    name: String,
+    fields: Vec<String>,
+}
+
+impl RecordType {
+    /// A new record type. Angle brackets around the name, as in `<point>`,
+    /// are dropped.
+    #[must_use]
+    pub fn new(name: &str, fields: Vec<String>) -> Self {
+        let name = name
+            .strip_prefix('<')
+            .and_then(|n| n.strip_suffix('>'))
+            .unwrap_or(name);
+
+        Self(Rc::new(TypeInfo {
+            id: next_id(),
+            name: name.to_string(),
+            fields,
+        }))
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    #[must_use]
+    pub fn fields(&self) -> &[String] {
+        &self.0.fields
+    }
+
+    /// The position of a field in records of this type.
+    #[must_use]
+    pub fn field_index(&self, field: &str) -> Option<usize> {
+        self.0.fields.iter().position(|f| f == field)
+    }
+}
+
+impl PartialEq for RecordType {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#[record-type {} {}]", self.0.id, self.0.name)
+    }
+}
+
+/// An instance of a record type. Clones share the same fields, and records
+/// are only equal to themselves.
+#[derive(Clone)]
+pub struct Record {
+    id: usize,
+    rtype: RecordType,
+    values: Rc<RefCell<Vec<SExp>>>,
+}
+
+impl Record {
+    /// A record with a value for each field of its type.
+    #[must_use]
+    pub fn new(rtype: RecordType, values: Vec<SExp>) -> Self {
+        Self {
+            id: next_id(),
+            rtype,
+            values: Rc::new(RefCell::new(values)),
+        }
+    }
+
+    #[must_use]
+    pub fn record_type(&self) -> &RecordType {
+        &self.rtype
+    }
+
+    #[must_use]
+    pub fn get(&self, i: usize) -> Option<SExp> {
+        self.values.borrow().get(i).cloned()
+    }
+
+    /// Replace the value at index `i`, returning whether there was one.
+    pub fn set(&self, i: usize, val: SExp) -> bool {
+        match self.values.borrow_mut().get_mut(i) {
+            Some(v) => {
+                *v = val;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.values, &other.values)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#[{} {}]", self.rtype.name(), self.id)
+    }
+}
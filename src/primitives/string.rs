@@ -0,0 +1,69 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt;
+use std::rc::Rc;
+
+/// A mutable string. Clones share the same characters, so changes are seen
+/// through every reference to the string.
+#[derive(Clone)]
+pub struct SharedString(Rc<RefCell<String>>);
+
+impl SharedString {
+    #[must_use]
+    pub fn new(s: String) -> Self {
+        Self(Rc::new(RefCell::new(s)))
+    }
+
+    /// Borrow the contents of the string.
+    ///
+    /// # Panics
+    /// Panics if the string is being mutated.
+    #[must_use]
+    pub fn borrow(&self) -> Ref<'_, String> {
+        self.0.borrow()
+    }
+
+    /// Mutably borrow the contents of the string.
+    ///
+    /// # Panics
+    /// Panics if the string is borrowed elsewhere.
+    #[must_use]
+    pub fn borrow_mut(&self) -> RefMut<'_, String> {
+        self.0.borrow_mut()
+    }
+
+    /// An address identifying the string, shared by all of its clones.
+    pub(crate) fn id(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+
+    /// Whether two strings are the same object, as opposed to merely having
+    /// equal contents.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl From<String> for SharedString {
+    fn from(s: String) -> Self {
+        Self::new(s)
+    }
+}
+
+impl From<&str> for SharedString {
+    fn from(s: &str) -> Self {
+        Self::new(s.to_string())
+    }
+}
+
+impl PartialEq for SharedString {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || *self.borrow() == *other.borrow()
+    }
+}
+
+impl fmt::Display for SharedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.borrow())
+    }
+}
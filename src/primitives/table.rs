@@ -0,0 +1,178 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use super::super::SExp::{self, Atom, Null, Pair};
+use super::Primitive::{
+    Boolean, Bytevector as Bytes, Character, Condition, Env, HashTable as Table, Number, Procedure,
+    Promise, Record, RecordType, String, Symbol, Undefined, Values, Vector, Void,
+};
+
+/// How deep into nested keys hashing looks; anything deeper only affects
+/// which keys share a bucket.
+const MAX_HASH_DEPTH: usize = 8;
+
+/// A mutable hash table. Clones share the same entries, so updates are seen
+/// through every reference to the table.
+#[derive(Clone)]
+pub struct HashTable(Rc<RefCell<Entries>>);
+
+struct Entries {
+    /// Whether keys are compared with `equal?` rather than `eqv?`.
+    equal: bool,
+    count: usize,
+    buckets: HashMap<u64, Vec<(SExp, SExp)>>,
+}
+
+fn keys_match(equal: bool, k0: &SExp, k1: &SExp) -> bool {
+    if equal {
+        k0.equal(k1)
+    } else {
+        k0.eqv(k1)
+    }
+}
+
+impl HashTable {
+    /// A table comparing keys with `equal?` if `equal` is set, and with
+    /// `eqv?` otherwise.
+    #[must_use]
+    pub fn new(equal: bool) -> Self {
+        Self(Rc::new(RefCell::new(Entries {
+            equal,
+            count: 0,
+            buckets: HashMap::new(),
+        })))
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &SExp) -> Option<SExp> {
+        let entries = self.0.borrow();
+        entries
+            .buckets
+            .get(&hash(entries.equal, key))?
+            .iter()
+            .find(|(k, _)| keys_match(entries.equal, k, key))
+            .map(|(_, v)| v.clone())
+    }
+
+    pub fn insert(&self, key: SExp, val: SExp) {
+        let mut entries = self.0.borrow_mut();
+        let equal = entries.equal;
+        let bucket = entries.buckets.entry(hash(equal, &key)).or_default();
+
+        if let Some(entry) = bucket.iter_mut().find(|(k, _)| keys_match(equal, k, &key)) {
+            entry.1 = val;
+        } else {
+            bucket.push((key, val));
+            entries.count += 1;
+        }
+    }
+
+    pub fn remove(&self, key: &SExp) -> Option<SExp> {
+        let mut entries = self.0.borrow_mut();
+        let (equal, h) = (entries.equal, hash(entries.equal, key));
+        let bucket = entries.buckets.get_mut(&h)?;
+        let idx = bucket.iter().position(|(k, _)| keys_match(equal, k, key))?;
+        let (_, val) = bucket.swap_remove(idx);
+
+        if bucket.is_empty() {
+            entries.buckets.remove(&h);
+        }
+        entries.count -= 1;
+        Some(val)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.borrow().count
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A snapshot of the table's entries, in no particular order.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(SExp, SExp)> {
+        self.0
+            .borrow()
+            .buckets
+            .values()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Tables are only equal to themselves.
+impl PartialEq for HashTable {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+fn hash(equal: bool, key: &SExp) -> u64 {
+    let mut state = DefaultHasher::new();
+    if equal {
+        hash_into(key, &mut state, MAX_HASH_DEPTH);
+    } else {
+        hash_eqv(key, &mut state);
+    }
+    state.finish()
+}
+
+/// Hash a key consistently with `eqv?`. Mutable objects are only `eqv?` to
+/// themselves, so their contents mustn't affect the hash.
+fn hash_eqv(key: &SExp, state: &mut DefaultHasher) {
+    match key {
+        Pair(p) => p.id().hash(state),
+        Atom(String(s)) => s.id().hash(state),
+        Atom(Vector(v)) => v.id().hash(state),
+        Atom(Bytes(b)) => b.id().hash(state),
+        _ => hash_into(key, state, MAX_HASH_DEPTH),
+    }
+}
+
+/// Hash a key consistently with `equal?`.
+#[allow(clippy::cast_possible_truncation)]
+fn hash_into(key: &SExp, state: &mut DefaultHasher, depth: usize) {
+    if depth == 0 {
+        return;
+    }
+
+    match key {
+        Null => 0.hash(state),
+        Pair(p) => {
+            1.hash(state);
+            hash_into(&p.head(), state, depth - 1);
+            hash_into(&p.tail(), state, depth - 1);
+        }
+        Atom(p) => match p {
+            Void | Undefined | Env(_) | Procedure(_) | Table(_) | Record(_) | RecordType(_)
+            | Condition(_) | Promise(_) | Values(_) => {
+                2.hash(state);
+            }
+            Boolean(b) => b.hash(state),
+            Character(c) => c.hash(state),
+            // `eqv?` numbers agree in exactness and value, and so in their
+            // integer part
+            Number(n) => {
+                n.is_exact().hash(state);
+                (f64::from(n.clone()).trunc() as i64).hash(state);
+            }
+            String(s) => s.borrow().hash(state),
+            Symbol(s) => s.hash(state),
+            Bytes(b) => b.borrow().hash(state),
+            Vector(v) => {
+                let v = v.borrow();
+                v.len().hash(state);
+                for e in v.iter().take(MAX_HASH_DEPTH) {
+                    hash_into(e, state, depth - 1);
+                }
+            }
+        },
+    }
+}
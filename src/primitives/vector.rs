@@ -0,0 +1,82 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+
+use super::super::SExp;
+use super::Primitive;
+
+/// A mutable vector. Clones share the same elements, so changes are seen
+/// through every reference to the vector.
+#[derive(Clone)]
+pub struct SharedVec(Rc<RefCell<Vec<SExp>>>);
+
+impl SharedVec {
+    #[must_use]
+    pub fn new(elements: Vec<SExp>) -> Self {
+        Self(Rc::new(RefCell::new(elements)))
+    }
+
+    /// Borrow the elements of the vector.
+    ///
+    /// # Panics
+    /// Panics if the vector is being mutated.
+    #[must_use]
+    pub fn borrow(&self) -> Ref<'_, Vec<SExp>> {
+        self.0.borrow()
+    }
+
+    /// Mutably borrow the elements of the vector.
+    ///
+    /// # Panics
+    /// Panics if the vector is borrowed elsewhere.
+    #[must_use]
+    pub fn borrow_mut(&self) -> RefMut<'_, Vec<SExp>> {
+        self.0.borrow_mut()
+    }
+
+    /// A copy of the element at index `i`, if there is one.
+    #[must_use]
+    pub fn get(&self, i: usize) -> Option<SExp> {
+        self.0.borrow().get(i).cloned()
+    }
+
+    /// A copy of the elements, independent of the vector.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<SExp> {
+        self.0.borrow().clone()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// An address identifying the vector, shared by all of its clones.
+    pub(crate) fn id(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+
+    /// Whether two vectors are the same object, as opposed to merely having
+    /// equal contents.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl From<Vec<SExp>> for SharedVec {
+    fn from(elements: Vec<SExp>) -> Self {
+        Self::new(elements)
+    }
+}
+
+impl PartialEq for SharedVec {
+    fn eq(&self, other: &Self) -> bool {
+        // compared as values, which copes with cycles
+        SExp::Atom(Primitive::Vector(self.clone())) == SExp::Atom(Primitive::Vector(other.clone()))
+    }
+}
@@ -2,6 +2,7 @@ use std::cmp::PartialEq;
 use std::fmt;
 use std::rc::Rc;
 
+use super::cont::{Dynamic, Link};
 use super::{Context, Env, Error, Primitive, Result, SExp};
 
 pub mod utils;
@@ -41,11 +42,11 @@ impl Proc {
     }
 
     pub(crate) fn defer_eval(&self) -> bool {
-        if let Func::Ctx(_) = self.func {
-            true
-        } else {
-            false
-        }
+        matches!(self.func, Func::Ctx(_) | Func::Macro(_))
+    }
+
+    pub(crate) fn is_macro(&self) -> bool {
+        matches!(self.func, Func::Macro(_))
     }
 
     pub(crate) fn is_tail(&self) -> bool {
@@ -56,24 +57,69 @@ impl Proc {
         }
     }
 
+    /// Expand a use of a macro, given its unevaluated operands.
+    ///
+    /// Returns `None` if this procedure is not a macro.
+    pub(crate) fn expand(&self, args: SExp, ctx: &mut Context) -> Option<Result> {
+        match &self.func {
+            Func::Macro(f) => Some(f(ctx, args)),
+            _ => None,
+        }
+    }
+
     pub fn apply(&self, args: SExp, ctx: &mut Context) -> Result {
-        self.check_arity(args.len())?;
+        self.check_arity(args.len()?)?;
 
         match &self.func {
             Func::Ctx(f) => f(ctx, args),
             Func::Pure(f) => f(args),
+            Func::Macro(f) => {
+                let expansion = f(ctx, args)?;
+                Ok(ctx.defer(expansion))
+            }
             Func::Tail { .. } => Ok(self.to_owned().into()),
-            Func::Lambda { body, envt, params } => {
+            Func::Parameter(p) => Ok(ctx.parameter_value(p)),
+            Func::Cont(..) => {
+                // any other number of values is passed on as a single object
+                let value = match args {
+                    SExp::Pair(p) if p.tail().is_empty() => p.car(),
+                    vals => Primitive::Values(vals.into_iter().collect()).into(),
+                };
+                Err(Error::Jump {
+                    cont: Box::new(self.to_owned().into()),
+                    value: Box::new(value),
+                })
+            }
+            Func::Lambda {
+                body,
+                envt,
+                params,
+                rest,
+            } => {
+                // match args to parameters before touching the scope, so that
+                // a failure leaves the context as it was
+                let mut args = args;
+                let mut bindings = Vec::with_capacity(params.len() + 1);
+                for p in params {
+                    let (v, tail) = args.split_car()?;
+                    bindings.push((p.clone(), v));
+                    args = tail;
+                }
+
+                // anything left over goes to the rest parameter
+                if let Some(r) = rest {
+                    bindings.push((r.clone(), args));
+                }
+
                 // start new scope and bind args to parameters
                 ctx.use_env(envt.clone());
                 ctx.push();
-                params
-                    .iter()
-                    .zip(args.into_iter())
-                    .for_each(|(p, v)| ctx.define(p, v));
+                for (p, v) in bindings {
+                    ctx.define(&p, v);
+                }
 
                 // evaluate each body expression, returning the last as a thunk
-                ctx.eval_defer(body)
+                Ok(ctx.eval_defer(body))
             }
         }
     }
@@ -85,6 +131,7 @@ impl PartialEq for Proc {
         match (&self.func, &other.func) {
             (Func::Ctx(p0), Func::Ctx(p1)) => Rc::ptr_eq(&p0, &p1),
             (Func::Pure(p0), Func::Pure(p1)) => Rc::ptr_eq(&p0, &p1),
+            (Func::Macro(m0), Func::Macro(m1)) => Rc::ptr_eq(m0, m1),
             (
                 Func::Lambda {
                     body: b0, envt: e0, ..
@@ -93,6 +140,8 @@ impl PartialEq for Proc {
                     body: b1, envt: e1, ..
                 },
             ) => Rc::ptr_eq(&b0, &b1) && Rc::ptr_eq(&e0, &e1),
+            (Func::Cont(k0, _), Func::Cont(k1, _)) => Rc::ptr_eq(&k0, &k1),
+            (Func::Parameter(p0), Func::Parameter(p1)) => Rc::ptr_eq(p0, p1),
             _ => false,
         }
     }
@@ -106,9 +155,12 @@ impl fmt::Debug for Proc {
 
 impl fmt::Display for Proc {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self.name {
-            Some(n) => write!(f, "#<procedure:{}>", n),
-            None => write!(f, "#<procedure>"),
+        match (&self.func, &self.name) {
+            (Func::Cont(..), _) => write!(f, "#<continuation>"),
+            (Func::Macro(_), Some(n)) => write!(f, "#<macro:{}>", n),
+            (Func::Macro(_), None) => write!(f, "#<macro>"),
+            (_, Some(n)) => write!(f, "#<procedure:{}>", n),
+            (_, None) => write!(f, "#<procedure>"),
         }
     }
 }
@@ -130,7 +182,7 @@ impl Arity {
         self.min == 0 && self.max == Some(0)
     }
 
-    fn check(&self, given: usize) -> std::result::Result<(), Error> {
+    pub(crate) fn check(&self, given: usize) -> std::result::Result<(), Error> {
         if given < self.min {
             match self.max {
                 Some(n) if n == self.min => Err(Error::Arity {
@@ -187,22 +239,39 @@ impl Into<SExp> for Arity {
     }
 }
 
-type CtxFn = dyn Fn(&mut Context, SExp) -> Result;
+pub(crate) type CtxFn = dyn Fn(&mut Context, SExp) -> Result;
 type PureFn = dyn Fn(SExp) -> Result;
 
 #[derive(Clone)]
 pub enum Func {
     Ctx(Rc<CtxFn>),
     Pure(Rc<PureFn>),
+    /// Receives its operands unevaluated and returns a new expression to be
+    /// evaluated in their place.
+    Macro(Rc<CtxFn>),
     Lambda {
         body: Rc<SExp>,
         envt: Rc<Env>,
         params: Vec<String>,
+        rest: Option<String>,
     },
     Tail {
         body: Rc<SExp>,
         envt: Rc<Env>,
     },
+    /// A captured continuation, along with the dynamic state it was
+    /// captured in, which may be newer than that of its top frame.
+    Cont(Link, Dynamic),
+    /// A parameter object made by `make-parameter`. Calling it returns its
+    /// value in the current dynamic extent.
+    Parameter(Rc<Parameter>),
+}
+
+/// The value a parameter object has outside of any `parameterize`, and the
+/// procedure that values given to it are passed through.
+pub struct Parameter {
+    pub init: SExp,
+    pub converter: Option<SExp>,
 }
 
 impl From<Rc<CtxFn>> for Func {
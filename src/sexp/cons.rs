@@ -0,0 +1,117 @@
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+use super::SExp;
+
+/// A pair of S-Expressions. Clones refer to the same cell, so changes made
+/// through [`set_car`](#method.set_car) or [`set_cdr`](#method.set_cdr) are
+/// visible through every reference to the pair.
+///
+/// # Example
+/// ```
+/// use parsley::{Cons, SExp};
+///
+/// let pair = Cons::new(SExp::from(1), SExp::Null);
+/// let alias = pair.clone();
+///
+/// pair.set_car(SExp::from(2));
+/// assert_eq!(alias.car(), SExp::from(2));
+/// assert!(pair.ptr_eq(&alias));
+/// ```
+#[derive(Clone)]
+pub struct Cons(Rc<Cell>);
+
+struct Cell {
+    head: RefCell<SExp>,
+    tail: RefCell<SExp>,
+}
+
+impl Cons {
+    #[must_use]
+    pub fn new(head: SExp, tail: SExp) -> Self {
+        Self(Rc::new(Cell {
+            head: RefCell::new(head),
+            tail: RefCell::new(tail),
+        }))
+    }
+
+    /// A copy of the first element of the pair.
+    #[must_use]
+    pub fn car(&self) -> SExp {
+        self.0.head.borrow().clone()
+    }
+
+    /// A copy of the second element of the pair.
+    #[must_use]
+    pub fn cdr(&self) -> SExp {
+        self.0.tail.borrow().clone()
+    }
+
+    /// Borrow the first element of the pair.
+    ///
+    /// # Panics
+    /// Panics if the pair is being mutated.
+    #[must_use]
+    pub fn head(&self) -> Ref<'_, SExp> {
+        self.0.head.borrow()
+    }
+
+    /// Borrow the second element of the pair.
+    ///
+    /// # Panics
+    /// Panics if the pair is being mutated.
+    #[must_use]
+    pub fn tail(&self) -> Ref<'_, SExp> {
+        self.0.tail.borrow()
+    }
+
+    pub fn set_car(&self, new: SExp) {
+        self.0.head.replace(new);
+    }
+
+    pub fn set_cdr(&self, new: SExp) {
+        self.0.tail.replace(new);
+    }
+
+    /// Whether two pairs are the same object, as opposed to merely having
+    /// equal contents.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// An address identifying the pair, shared by all of its clones.
+    pub(crate) fn id(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+
+    /// Both elements of the pair, moved out if nothing else refers to it.
+    #[must_use]
+    pub fn into_parts(self) -> (SExp, SExp) {
+        match Rc::try_unwrap(self.0) {
+            Ok(cell) => (cell.head.replace(SExp::Null), cell.tail.replace(SExp::Null)),
+            Err(rc) => (rc.head.borrow().clone(), rc.tail.borrow().clone()),
+        }
+    }
+}
+
+impl PartialEq for Cons {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || SExp::Pair(self.clone()) == SExp::Pair(other.clone())
+    }
+}
+
+// unlink the rest of a list iteratively, so that dropping a long one doesn't
+// recurse once per element
+impl Drop for Cell {
+    fn drop(&mut self) {
+        let mut next = self.tail.replace(SExp::Null);
+
+        while let SExp::Pair(Cons(rc)) = next {
+            match Rc::try_unwrap(rc) {
+                Ok(cell) => next = cell.tail.replace(SExp::Null),
+                Err(_) => break,
+            }
+        }
+    }
+}
@@ -1,55 +1,90 @@
+use super::Cons;
 use super::Primitive::Symbol;
 use super::SExp::{self, Atom, Null, Pair};
+use std::cell::RefCell;
 use std::fmt;
 
+type ItemFmt = fn(&SExp, &mut fmt::Formatter) -> fmt::Result;
+
+thread_local! {
+    /// The pairs and vectors currently being written, outermost first.
+    static WRITING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Write `e` with `write`, or `...` if it contains itself and is already
+/// being written further out.
+fn fmt_guarded(e: &SExp, f: &mut fmt::Formatter, write: ItemFmt) -> fmt::Result {
+    match e.cycle_id() {
+        Some(id) if WRITING.with(|w| w.borrow().contains(&id)) => f.write_str("..."),
+        Some(id) => {
+            WRITING.with(|w| w.borrow_mut().push(id));
+            let result = write(e, f);
+            WRITING.with(|w| w.borrow_mut().pop());
+            result
+        }
+        None => write(e, f),
+    }
+}
+
+/// Write a pair, using `item` for its elements.
+fn fmt_pair(p: &Cons, f: &mut fmt::Formatter, item: ItemFmt) -> fmt::Result {
+    // only a proper `(quote x)` can be written as `'x`
+    if let (Atom(Symbol(q)), Pair(t)) = (&*p.head(), &*p.tail()) {
+        if q == "quote" && *t.tail() == Null {
+            f.write_str("'")?;
+            return item(&t.head(), f);
+        }
+    }
+
+    f.write_str("(")?;
+    item(&p.head(), f)?;
+
+    let mut rest = p.cdr();
+    // trails behind at half speed to catch circular lists
+    let mut slow = p.clone();
+    let mut n = 0;
+    while let Pair(next) = rest {
+        f.write_str(" ")?;
+        item(&next.head(), f)?;
+        rest = next.cdr();
+
+        n += 1;
+        if n % 2 == 0 {
+            slow = match slow.cdr() {
+                Pair(s) => s,
+                _ => unreachable!("The slow pointer only visits pairs."),
+            };
+            if let Pair(r) = &rest {
+                if r.ptr_eq(&slow) {
+                    return f.write_str(" ...)");
+                }
+            }
+        }
+    }
+
+    if let Atom(_) = rest {
+        f.write_str(" . ")?;
+        item(&rest, f)?;
+    }
+    f.write_str(")")
+}
+
 impl fmt::Debug for SExp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
+        fmt_guarded(self, f, |e, f| match e {
             Null => write!(f, "()",),
             Atom(a) => write!(f, "{:?}", a),
-            Pair { head, tail } => match &**head {
-                Atom(Symbol(q)) if q == "quote" => match &**tail {
-                    Pair { head: h2, tail: t2 } if **t2 == Null => write!(f, "'{}", h2),
-                    _ => write!(f, "'{}", tail),
-                },
-                _ => {
-                    write!(f, "({:?}", head)?;
-                    match &**tail {
-                        Atom(a) => write!(f, " . {:?}", a)?,
-                        null_or_pair => null_or_pair
-                            .iter()
-                            .map(|item| write!(f, " {:?}", item))
-                            .collect::<fmt::Result>()?,
-                    }
-                    write!(f, ")")
-                }
-            },
-        }
+            Pair(p) => fmt_pair(p, f, fmt::Debug::fmt),
+        })
     }
 }
 
 impl fmt::Display for SExp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
+        fmt_guarded(self, f, |e, f| match e {
             Null => write!(f, "()",),
             Atom(a) => write!(f, "{}", a),
-            Pair { head, tail } => match &**head {
-                Atom(Symbol(q)) if q == "quote" => match &**tail {
-                    Pair { head: h2, tail: t2 } if **t2 == Null => write!(f, "'{}", h2),
-                    _ => write!(f, "'{}", tail),
-                },
-                _ => {
-                    write!(f, "({}", head)?;
-                    match &**tail {
-                        Atom(a) => write!(f, " . {}", a)?,
-                        null_or_pair => null_or_pair
-                            .iter()
-                            .map(|item| write!(f, " {}", item))
-                            .collect::<fmt::Result>()?,
-                    }
-                    write!(f, ")")
-                }
-            },
-        }
+            Pair(p) => fmt_pair(p, f, fmt::Display::fmt),
+        })
     }
 }
@@ -1,5 +1,7 @@
-use super::super::Primitive;
-use super::SExp::{self, Atom, Null, Pair};
+use std::convert::TryFrom;
+
+use super::super::{Error, Primitive};
+use super::SExp::{self, Atom, Null};
 
 /// Construct an S-Expression from a list of expressions.
 ///
@@ -39,10 +41,7 @@ where
     SExp: From<T>,
 {
     fn from((v,): (T,)) -> Self {
-        Pair {
-            head: Box::new(Self::from(v)),
-            tail: Box::new(Null),
-        }
+        Null.cons(Self::from(v))
     }
 }
 
@@ -51,10 +50,7 @@ where
     SExp: From<T> + From<U>,
 {
     fn from((v1, v2): (T, U)) -> Self {
-        Pair {
-            head: Box::new(Self::from(v1)),
-            tail: Box::new(Self::from(v2)),
-        }
+        Self::from(v2).cons(Self::from(v1))
     }
 }
 
@@ -75,3 +71,18 @@ where
         ary.into_iter().map(T::into).collect()
     }
 }
+
+/// Copy the bytes out of a bytevector.
+impl TryFrom<SExp> for Vec<u8> {
+    type Error = Error;
+
+    fn try_from(e: SExp) -> Result<Self, Error> {
+        match e {
+            Atom(Primitive::Bytevector(b)) => Ok(b.to_vec()),
+            _ => Err(Error::Type {
+                expected: "bytevector",
+                given: e.type_of().to_string(),
+            }),
+        }
+    }
+}
@@ -1,27 +1,50 @@
 use std::iter::FromIterator;
-use std::ops::Index;
 
-use super::SExp::{self, Atom, Null, Pair};
+use super::Cons;
+use super::Error;
+use super::SExp::{self, Null, Pair};
 
 /// An iterator over an S-Expression. Returns list elements until the end of a chain of pairs.
+///
+/// The final `cdr` of an improper list is not an element, so it is never
+/// returned; it is left in [`rest`](#method.rest) once iteration stops.
 pub struct SExpIterator {
     exp: SExp,
 }
 
+impl SExpIterator {
+    /// What is left of the expression: `Null` once a proper list has been
+    /// used up, and the final `cdr` once an improper one has.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::prelude::*;
+    /// let mut iter = SExp::from(3).cons(SExp::from(1)).into_iter();
+    /// assert_eq!(iter.next(), Some(SExp::from(1)));
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.rest(), &SExp::from(3));
+    /// ```
+    #[must_use]
+    pub fn rest(&self) -> &SExp {
+        &self.exp
+    }
+}
+
 impl Iterator for SExpIterator {
     type Item = SExp;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.exp.to_owned() {
-            Pair { head, tail } => {
-                self.exp = *tail;
-                Some(*head)
+        match std::mem::replace(&mut self.exp, Null) {
+            Pair(p) => {
+                let (head, tail) = p.into_parts();
+                self.exp = tail;
+                Some(head)
             }
-            a @ Atom(_) => {
-                self.exp = Null;
-                Some(a)
+            // keep an improper tail around for `rest`
+            other => {
+                self.exp = other;
+                None
             }
-            _ => None,
         }
     }
 }
@@ -35,42 +58,21 @@ impl IntoIterator for SExp {
     }
 }
 
-pub struct SExpRefIterator<'a> {
-    exp: &'a SExp,
-}
-
-impl<'a> Iterator for SExpRefIterator<'a> {
-    type Item = &'a SExp;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.exp {
-            Pair { head, tail } => {
-                self.exp = &*tail;
-                Some(&*head)
-            }
-            a @ Atom(_) => {
-                self.exp = &Null;
-                Some(&a)
-            }
-            Null => None,
-        }
-    }
-}
-
 impl SExp {
-    /// Iterate over an S-Expression, by reference.
+    /// Iterate over an S-Expression without consuming it. Pairs are shared
+    /// rather than copied, so this is cheap.
     ///
     /// # Example
     /// ```
     /// use parsley::prelude::*;
     /// assert_eq!(
     ///     sexp![()].iter().next().unwrap(),
-    ///     &SExp::Null
+    ///     SExp::Null
     /// );
     /// ```
     #[must_use]
-    pub fn iter(&self) -> SExpRefIterator {
-        SExpRefIterator { exp: &self }
+    pub fn iter(&self) -> SExpIterator {
+        SExpIterator { exp: self.clone() }
     }
 
     /// Easy way to check for `Null` if you're planning on iterating
@@ -83,27 +85,51 @@ impl SExp {
         }
     }
 
-    /// Get the length of an S-Expression (vector or list)
+    /// Get the length of a list.
+    ///
+    /// # Errors
+    /// Returns `Err` if the expression is not a proper (`Null`-terminated) list,
+    /// including if it is circular.
     ///
     /// # Example
     /// ```
     /// use parsley::prelude::*;
     /// assert_eq!(
-    ///     sexp!['a', "bee", SExp::sym("sea")].len(),
+    ///     sexp!['a', "bee", SExp::sym("sea")].len().unwrap(),
     ///     3
     /// );
+    /// assert!(SExp::from(3).len().is_err());
+    /// assert!(SExp::from(3).cons(SExp::from(2)).len().is_err());
     /// ```
-    #[must_use]
-    pub fn len(&self) -> usize {
-        self.iter().count()
-    }
-}
-
-impl Index<usize> for SExp {
-    type Output = Self;
+    pub fn len(&self) -> ::std::result::Result<usize, Error> {
+        let mut n = 0;
+        let mut rest = self.clone();
+        // trails behind at half speed, so it can only be caught up with by
+        // going around a cycle
+        let mut slow = self.clone();
+
+        while let Pair(p) = rest {
+            n += 1;
+            rest = p.cdr();
+
+            if n % 2 == 0 {
+                slow = slow.cdr()?;
+                if let (Pair(p0), Pair(p1)) = (&slow, &rest) {
+                    if p0.ptr_eq(p1) {
+                        return Err(Error::NotAList {
+                            atom: "#<circular list>".to_string(),
+                        });
+                    }
+                }
+            }
+        }
 
-    fn index(&self, index: usize) -> &Self::Output {
-        self.iter().nth(index).unwrap()
+        match rest {
+            Null => Ok(n),
+            _ => Err(Error::NotAList {
+                atom: self.to_string(),
+            }),
+        }
     }
 }
 
@@ -112,27 +138,19 @@ impl FromIterator<SExp> for SExp {
     where
         I: IntoIterator<Item = SExp>,
     {
-        let mut exp_out = Null;
-        let mut last = &mut exp_out;
+        let mut iter = iter.into_iter();
+        let first = match iter.next() {
+            Some(exp) => Cons::new(exp, Null),
+            None => return Null,
+        };
 
+        let mut last = first.clone();
         for exp in iter {
-            let new_val = Pair {
-                head: Box::new(exp),
-                tail: Box::new(Null),
-            };
-
-            match last {
-                Null => {
-                    *last = new_val;
-                }
-                Pair { ref mut tail, .. } => {
-                    *tail = Box::new(new_val);
-                    last = tail;
-                }
-                _ => (),
-            }
+            let next = Cons::new(exp, Null);
+            last.set_cdr(Pair(next.clone()));
+            last = next;
         }
 
-        exp_out
+        Pair(first)
     }
 }
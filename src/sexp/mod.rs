@@ -1,13 +1,17 @@
 #[macro_use]
 mod from;
 
+mod cons;
 mod display;
 mod eval;
 mod iter;
 mod parse;
 
+use std::collections::HashSet;
+
 use super::{utils, Error, Primitive, Result, SyntaxError};
 
+pub use self::cons::Cons;
 use self::SExp::{Atom, Null, Pair};
 
 /// An S-Expression. Can be parsed from a string via `FromStr`, or constructed
@@ -24,11 +28,11 @@ use self::SExp::{Atom, Null, Pair};
 /// let parsed = "\"abcdefg\"".parse::<SExp>().unwrap();
 /// assert_eq!(parsed, SExp::from("abcdefg"));
 /// ```
-#[derive(PartialEq, Clone)]
+#[derive(Clone)]
 pub enum SExp {
     Null,
     Atom(Primitive),
-    Pair { head: Box<SExp>, tail: Box<SExp> },
+    Pair(Cons),
 }
 
 impl SExp {
@@ -38,7 +42,7 @@ impl SExp {
             Atom(_) => Err(Error::NotAList {
                 atom: self.to_string(),
             }),
-            Pair { head, tail } => Ok((*head, *tail)),
+            Pair(p) => Ok(p.into_parts()),
         }
     }
 
@@ -50,27 +54,27 @@ impl SExp {
         Ok(self.split_car()?.1)
     }
 
-    pub(super) fn set_car(&mut self, new: Self) -> Result {
+    pub(super) fn set_car(&self, new: Self) -> Result {
         match self {
             Null => Err(Error::NullList),
             Atom(_) => Err(Error::NotAList {
                 atom: self.to_string(),
             }),
-            Pair { head, .. } => {
-                *head = Box::new(new);
+            Pair(p) => {
+                p.set_car(new);
                 Ok(Atom(Primitive::Undefined))
             }
         }
     }
 
-    pub(super) fn set_cdr(&mut self, new: Self) -> Result {
+    pub(super) fn set_cdr(&self, new: Self) -> Result {
         match self {
             Null => Err(Error::NullList),
             Atom(_) => Err(Error::NotAList {
                 atom: self.to_string(),
             }),
-            Pair { tail, .. } => {
-                *tail = Box::new(new);
+            Pair(p) => {
+                p.set_cdr(new);
                 Ok(Atom(Primitive::Undefined))
             }
         }
@@ -90,10 +94,7 @@ impl SExp {
     /// ```
     #[must_use]
     pub fn cons(self, exp: Self) -> Self {
-        Pair {
-            head: Box::new(exp),
-            tail: Box::new(self),
-        }
+        Pair(Cons::new(exp, self))
     }
 
     /// Convenience method to build a symbolic atom.
@@ -134,4 +135,116 @@ impl SExp {
             Pair { .. } => "list",
         }
     }
+
+    /// The values an expression stands for: each of the values returned by a
+    /// call to `values`, or otherwise just the expression itself.
+    ///
+    /// # Example
+    /// ```
+    /// use parsley::prelude::*;
+    ///
+    /// let vals = run("(values 1 2)").unwrap().into_values();
+    /// assert_eq!(vals, vec![SExp::from(1), SExp::from(2)]);
+    /// assert_eq!(run("3").unwrap().into_values(), vec![SExp::from(3)]);
+    /// ```
+    #[must_use]
+    pub fn into_values(self) -> Vec<Self> {
+        match self {
+            Atom(Primitive::Values(vals)) => vals,
+            val => vec![val],
+        }
+    }
+
+    /// An address identifying a pair or vector, which are the values that
+    /// can be part of a cycle.
+    fn cycle_id(&self) -> Option<usize> {
+        match self {
+            Pair(p) => Some(p.id()),
+            Atom(Primitive::Vector(v)) => Some(v.id()),
+            _ => None,
+        }
+    }
+
+    /// Equivalence in the sense of `eqv?`.
+    pub(super) fn eqv(&self, other: &Self) -> bool {
+        use Primitive::{
+            Boolean, Bytevector, Character, Condition, HashTable, Number, Procedure, Promise,
+            Record, RecordType, String, Symbol, Vector,
+        };
+
+        match (self, other) {
+            (Null, Null) => true,
+            (Pair(p0), Pair(p1)) => p0.ptr_eq(p1),
+            (Atom(Boolean(b0)), Atom(Boolean(b1))) => b0 == b1,
+            (Atom(Character(c0)), Atom(Character(c1))) => c0 == c1,
+            (Atom(Symbol(s0)), Atom(Symbol(s1))) => s0 == s1,
+            (Atom(Number(n0)), Atom(Number(n1))) => n0.eqv(n1),
+            (Atom(Procedure(p0)), Atom(Procedure(p1))) => p0 == p1,
+            (Atom(HashTable(t0)), Atom(HashTable(t1))) => t0 == t1,
+            (Atom(String(s0)), Atom(String(s1))) => s0.ptr_eq(s1),
+            (Atom(Vector(v0)), Atom(Vector(v1))) => v0.ptr_eq(v1),
+            (Atom(Bytevector(b0)), Atom(Bytevector(b1))) => b0.ptr_eq(b1),
+            (Atom(Record(r0)), Atom(Record(r1))) => r0 == r1,
+            (Atom(RecordType(t0)), Atom(RecordType(t1))) => t0 == t1,
+            (Atom(Condition(c0)), Atom(Condition(c1))) => c0 == c1,
+            (Atom(Promise(p0)), Atom(Promise(p1))) => p0 == p1,
+            _ => false,
+        }
+    }
+
+    /// Equivalence in the sense of `equal?`: like `==`, except that numbers
+    /// are compared with `eqv?`.
+    pub(crate) fn equal(&self, other: &Self) -> bool {
+        self.compare_with(other, |a0, a1| match (a0, a1) {
+            (Primitive::Number(n0), Primitive::Number(n1)) => n0.eqv(n1),
+            _ => a0 == a1,
+        })
+    }
+
+    /// Compare two expressions structurally, using `atoms_eq` for atoms.
+    /// Pairs and vectors are compared without recursing, and comparisons
+    /// already underway are assumed to hold, so that circular structures can
+    /// be compared as well.
+    fn compare_with(&self, other: &Self, atoms_eq: fn(&Primitive, &Primitive) -> bool) -> bool {
+        let mut pending = vec![(self.clone(), other.clone())];
+        let mut seen = HashSet::new();
+
+        while let Some((e0, e1)) = pending.pop() {
+            if let (Some(id0), Some(id1)) = (e0.cycle_id(), e1.cycle_id()) {
+                if id0 == id1 || !seen.insert((id0, id1)) {
+                    continue;
+                }
+            }
+
+            match (e0, e1) {
+                (Null, Null) => {}
+                (Pair(p0), Pair(p1)) => {
+                    pending.push((p0.cdr(), p1.cdr()));
+                    pending.push((p0.car(), p1.car()));
+                }
+                (Atom(Primitive::Vector(v0)), Atom(Primitive::Vector(v1))) => {
+                    let (v0, v1) = (v0.to_vec(), v1.to_vec());
+                    if v0.len() != v1.len() {
+                        return false;
+                    }
+                    pending.extend(v0.into_iter().zip(v1).rev());
+                }
+                (Atom(a0), Atom(a1)) => {
+                    if !atoms_eq(&a0, &a1) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// Structural equality, comparing numbers by value alone.
+impl PartialEq for SExp {
+    fn eq(&self, other: &Self) -> bool {
+        self.compare_with(other, Primitive::eq)
+    }
 }
@@ -1,6 +1,8 @@
+use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
 
+use super::super::{Bytevector, Num};
 use super::{
     utils, Error, Primitive, Result,
     SExp::{self, Atom, Null},
@@ -36,6 +38,7 @@ impl From<&Paren> for char {
 enum Token {
     OpenParen(Paren),
     OpenHashParen(Paren),
+    OpenBytevector,
     CloseParen(Paren),
     Quote,
     Quasiquote,
@@ -54,6 +57,7 @@ impl Token {
             "#(" => Some(Token::OpenHashParen(Paren::Round)),
             "#[" => Some(Token::OpenHashParen(Paren::Square)),
             "#{" => Some(Token::OpenHashParen(Paren::Curly)),
+            "#u8(" => Some(Token::OpenBytevector),
             ")" => Some(Token::CloseParen(Paren::Round)),
             "]" => Some(Token::CloseParen(Paren::Square)),
             "}" => Some(Token::CloseParen(Paren::Curly)),
@@ -73,11 +77,13 @@ impl FromStr for Token {
         if let Some(t) = Self::from_sigil(s) {
             Ok(t)
         } else {
-            if s.starts_with('"') && s.ends_with('"') {
-                return Ok(Token::StringLiteral(s[1..s.len() - 1].into()));
+            if let Some(body) = s.strip_prefix('"') {
+                if let Ok((lit, "")) = read_string_literal(body) {
+                    return Ok(Token::StringLiteral(lit));
+                }
             }
 
-            if s.chars().all(utils::is_atom_char) {
+            if s.starts_with("#\\") || s.chars().all(utils::is_atom_char) {
                 return Ok(Token::Atom(s.into()));
             }
 
@@ -101,27 +107,23 @@ fn get_next_token(s: &str) -> std::result::Result<(Option<Token>, &str), SyntaxE
     }
 
     // special handling for string literals
-    if s.starts_with('"') {
-        let mut pos = 1;
-        let mut esc = false;
-        for c in s[1..].chars() {
-            match c {
-                '\\' => esc = !esc,
-                '"' if !esc => break,
-                _ => esc = false,
-            }
-            pos += 1;
-        }
-        if pos == s.len() - 1 && !s.ends_with('"') {
-            return Err(SyntaxError::UnmatchedQuote(s.into()));
-        } else {
-            return Ok((Some(s[..=pos].parse()?), &s[pos + 1..]));
-        }
+    if let Some(rest) = s.strip_prefix('"') {
+        let (lit, rest) = read_string_literal(rest)?;
+        return Ok((Some(Token::StringLiteral(lit)), rest));
+    }
+
+    // character literals may be followed by any character, even a delimiter
+    if let Some(rest) = s.strip_prefix("#\\") {
+        let first = rest.chars().next().map_or(0, char::len_utf8);
+        let pos = rest[first..]
+            .find(|c| !utils::is_atom_char(c))
+            .map_or(s.len(), |i| i + first + 2);
+        return Ok((Some(s[..pos].parse()?), &s[pos..]));
     }
 
-    // sigils - can be 1 or 2 chars
-    for len in 1..3 {
-        if len <= s.len() {
+    // sigils - can be 1 to 4 chars, and the longest match wins (`,@` vs `,`)
+    for len in (1..5).rev() {
+        if s.is_char_boundary(len) {
             let (t, rest) = s.split_at(len);
             if let Some(tok) = Token::from_sigil(t) {
                 return Ok((Some(tok), rest));
@@ -136,6 +138,63 @@ fn get_next_token(s: &str) -> std::result::Result<(Option<Token>, &str), SyntaxE
     Ok((Some(s[..pos].parse()?), &s[pos..]))
 }
 
+/// Decode the body of a string literal up to its closing quote, returning
+/// the decoded string and the input following the quote.
+fn read_string_literal(s: &str) -> std::result::Result<(String, &str), SyntaxError> {
+    const INTRALINE: [char; 3] = [' ', '\t', '\r'];
+
+    let unmatched = || SyntaxError::UnmatchedQuote(format!("\"{s}"));
+    let mut lit = String::new();
+    let mut rest = s;
+
+    loop {
+        let mut chars = rest.chars();
+        match chars.next().ok_or_else(unmatched)? {
+            '"' => return Ok((lit, chars.as_str())),
+            '\\' => match chars.next().ok_or_else(unmatched)? {
+                'a' => lit.push('\u{7}'),
+                'b' => lit.push('\u{8}'),
+                't' => lit.push('\t'),
+                'n' => lit.push('\n'),
+                'r' => lit.push('\r'),
+                '0' => lit.push('\0'),
+                esc @ ('"' | '\\' | '|') => lit.push(esc),
+                'x' => {
+                    let body = chars.as_str();
+                    let end = body
+                        .find(';')
+                        .ok_or_else(|| SyntaxError::InvalidEscape(format!("x{body}")))?;
+                    let c = u32::from_str_radix(&body[..end], 16)
+                        .ok()
+                        .and_then(std::char::from_u32)
+                        .ok_or_else(|| SyntaxError::InvalidEscape(format!("x{};", &body[..end])))?;
+                    lit.push(c);
+                    rest = &body[end + 1..];
+                    continue;
+                }
+                // a line continuation skips the line ending along with any
+                // whitespace around it
+                esc if esc == '\n' || INTRALINE.contains(&esc) => {
+                    let after = if esc == '\n' {
+                        chars.as_str()
+                    } else {
+                        chars
+                            .as_str()
+                            .trim_start_matches(INTRALINE)
+                            .strip_prefix('\n')
+                            .ok_or_else(|| SyntaxError::InvalidEscape(esc.to_string()))?
+                    };
+                    rest = after.trim_start_matches(INTRALINE);
+                    continue;
+                }
+                esc => return Err(SyntaxError::InvalidEscape(esc.to_string())),
+            },
+            c => lit.push(c),
+        }
+        rest = chars.as_str();
+    }
+}
+
 fn lex(mut s: &str) -> std::result::Result<Vec<Token>, SyntaxError> {
     let mut tokens = Vec::new();
 
@@ -150,16 +209,18 @@ fn lex(mut s: &str) -> std::result::Result<Vec<Token>, SyntaxError> {
     Ok(tokens)
 }
 
+/// Parse the contents of a list, returning its elements and its final `cdr`
+/// (`Null` unless the list was written in dotted notation).
 fn parse_list_tokens<'a>(
     tokens: &'a [Token],
     paren_type: Paren,
-) -> std::result::Result<(Vec<SExp>, &'a [Token]), SyntaxError> {
+) -> std::result::Result<(Vec<SExp>, SExp, &'a [Token]), SyntaxError> {
     let mut idx = 1;
     let mut n = 0;
 
     for tok in &tokens[1..] {
         match *tok {
-            Token::OpenParen(_) | Token::OpenHashParen(_) => n += 1,
+            Token::OpenParen(_) | Token::OpenHashParen(_) | Token::OpenBytevector => n += 1,
             Token::CloseParen(p) if n == 0 && p == paren_type => break,
             Token::CloseParen(ref p) if n == 0 => {
                 return Err(SyntaxError::UnmatchedParen {
@@ -184,14 +245,32 @@ fn parse_list_tokens<'a>(
 
     let mut list_tokens = &tokens[1..idx];
     let mut list_out = Vec::new();
+    let mut last = Null;
 
     while !list_tokens.is_empty() {
+        if let Token::Atom(ref dot) = list_tokens[0] {
+            if dot == "." {
+                // exactly one expression must follow the dot, after at least one before it
+                let (expr, new_list_tokens) = match &list_tokens[1..] {
+                    rest if !rest.is_empty() && !list_out.is_empty() => get_next_sexp(rest)?,
+                    _ => return Err(SyntaxError::UnexpectedDot(format!("{:?}", tokens))),
+                };
+
+                if !new_list_tokens.is_empty() {
+                    return Err(SyntaxError::UnexpectedDot(format!("{:?}", tokens)));
+                }
+
+                last = expr;
+                break;
+            }
+        }
+
         let (expr, new_list_tokens) = get_next_sexp(list_tokens)?;
         list_tokens = new_list_tokens;
         list_out.push(expr);
     }
 
-    Ok((list_out, &tokens[idx + 1..]))
+    Ok((list_out, last, &tokens[idx + 1..]))
 }
 
 fn dequote(mut tokens: &[Token]) -> (Vec<SExp>, &[Token]) {
@@ -213,19 +292,43 @@ fn dequote(mut tokens: &[Token]) -> (Vec<SExp>, &[Token]) {
     (v, tokens)
 }
 
+fn to_byte(e: SExp) -> std::result::Result<u8, SyntaxError> {
+    let byte = match &e {
+        Atom(Primitive::Number(Num::Int(i))) => u8::try_from(*i).ok(),
+        _ => None,
+    };
+    byte.ok_or(SyntaxError::NotAByte(e))
+}
+
 fn get_next_sexp(tokens: &[Token]) -> std::result::Result<(SExp, &[Token]), SyntaxError> {
     let (prefixes, tokens) = dequote(tokens);
 
     let mut quotable = match tokens.split_first() {
         Some((Token::Atom(s), rest)) => (Atom(s.parse()?), rest),
-        Some((Token::StringLiteral(s), rest)) => (Atom(Primitive::String(s.to_string())), rest),
+        Some((Token::StringLiteral(s), rest)) => (Atom(Primitive::String(s.as_str().into())), rest),
         Some((Token::OpenParen(paren_type), rest)) => match rest.split_first() {
             Some((Token::CloseParen(p), rest)) if p == paren_type => (Null, rest),
-            _ => parse_list_tokens(tokens, *paren_type).map(|(v, t)| (v.into(), t))?,
+            _ => {
+                let (v, last, rest) = parse_list_tokens(tokens, *paren_type)?;
+                (v.into_iter().rev().fold(last, SExp::cons), rest)
+            }
         },
         Some((Token::OpenHashParen(paren_type), _)) => {
-            parse_list_tokens(tokens, *paren_type).map(|(v, t)| (Atom(Primitive::Vector(v)), t))?
+            match parse_list_tokens(tokens, *paren_type)? {
+                (v, Null, rest) => (Atom(Primitive::Vector(v.into())), rest),
+                _ => return Err(SyntaxError::UnexpectedDot(format!("{:?}", tokens))),
+            }
         }
+        Some((Token::OpenBytevector, _)) => match parse_list_tokens(tokens, Paren::Round)? {
+            (v, Null, rest) => {
+                let bytes = v
+                    .into_iter()
+                    .map(to_byte)
+                    .collect::<std::result::Result<_, _>>()?;
+                (Atom(Primitive::Bytevector(Bytevector::new(bytes))), rest)
+            }
+            _ => return Err(SyntaxError::UnexpectedDot(format!("{:?}", tokens))),
+        },
         _ => unreachable!("`get_next_sexp` should only be called with a non-empty list of tokens."),
     };
 
@@ -70,6 +70,91 @@ fn primitive_types() {
     );
 }
 
+#[test]
+fn character_literals() {
+    do_parse_and_assert("#\\space", SExp::from(' '));
+    do_parse_and_assert("#\\newline", SExp::from('\n'));
+    do_parse_and_assert("#\\tab", SExp::from('\t'));
+    do_parse_and_assert("#\\nul", SExp::from('\0'));
+    do_parse_and_assert("#\\x41", SExp::from('A'));
+    do_parse_and_assert("#\\x", SExp::from('x'));
+    do_parse_and_assert("#\\λ", SExp::from('λ'));
+    do_parse_and_assert(
+        "(#\\( #\\) #\\ )",
+        Null.cons(SExp::from(' '))
+            .cons(SExp::from(')'))
+            .cons(SExp::from('(')),
+    );
+    assert!("#\\bogus".parse::<SExp>().is_err());
+    assert_eq!(format!("{:?}", SExp::from(' ')), "#\\space");
+    assert_eq!(format!("{:?}", SExp::from('\u{1}')), "#\\x1");
+}
+
+#[test]
+fn string_escapes() {
+    do_parse_and_assert(r#""a\nb""#, SExp::from("a\nb"));
+    do_parse_and_assert(r#""\"\\\t\a\|""#, SExp::from("\"\\\t\u{7}|"));
+    do_parse_and_assert(r#""\x41;\x3bb;""#, SExp::from("Aλ"));
+    do_parse_and_assert("\"one \\   \n    two\"", SExp::from("one two"));
+    do_parse_and_assert("\"one\\\ntwo\"", SExp::from("onetwo"));
+    assert!(r#""\x41""#.parse::<SExp>().is_err());
+    assert!(r#""\q""#.parse::<SExp>().is_err());
+    assert!(r#""unterminated"#.parse::<SExp>().is_err());
+}
+
+#[test]
+fn bytevectors() {
+    use crate::Bytevector;
+
+    do_parse_and_assert("#u8()", Bytevector::from(vec![]).into());
+    do_parse_and_assert(
+        "(#u8(0 16 255) x)",
+        sexp![Bytevector::from(vec![0, 16, 255]), SExp::sym("x")],
+    );
+    assert_eq!(
+        format!("{}", SExp::from(Bytevector::from(vec![1, 2]))),
+        "#u8(1 2)"
+    );
+    assert!("#u8(256)".parse::<SExp>().is_err());
+    assert!("#u8(1.5)".parse::<SExp>().is_err());
+    assert!("#u8(1 . 2)".parse::<SExp>().is_err());
+}
+
+#[test]
+fn rationals() {
+    use super::super::super::Num;
+
+    do_parse_and_assert("1/3", SExp::from(Num::from(1) / 3));
+    do_parse_and_assert("-2/4", SExp::from(Num::from(-1) / 2));
+    do_parse_and_assert("4/2", SExp::from(2));
+    assert_eq!("3/4".parse::<SExp>().unwrap().to_string(), "3/4");
+    do_parse_and_assert("1/-2", SExp::sym("1/-2"));
+}
+
+#[test]
+fn big_integers() {
+    for n in &[
+        "9223372036854775808",
+        "-9223372036854775809",
+        "123456789012345678901234567890",
+    ] {
+        assert_eq!(n.parse::<SExp>().unwrap().to_string(), *n);
+    }
+}
+
+#[test]
+fn complex_numbers() {
+    use super::super::super::Num::Complex;
+
+    do_parse_and_assert("3+4i", SExp::from(Complex(3., 4.)));
+    do_parse_and_assert("-1.5-2i", SExp::from(Complex(-1.5, -2.)));
+    do_parse_and_assert("+i", SExp::from(Complex(0., 1.)));
+    do_parse_and_assert("1e2-1e-2i", SExp::from(Complex(100., -0.01)));
+    do_parse_and_assert("2+0i", SExp::from(2));
+    assert_eq!("3-4i".parse::<SExp>().unwrap().to_string(), "3.0-4.0i");
+    do_parse_and_assert("a+bi", SExp::sym("a+bi"));
+}
+
 #[test]
 fn mixed_type_list() {
     do_parse_and_assert(
@@ -86,6 +171,29 @@ fn mixed_type_list() {
     );
 }
 
+#[test]
+fn dotted_pairs() {
+    do_parse_and_assert("(a . b)", SExp::sym("b").cons(SExp::sym("a")));
+    do_parse_and_assert(
+        "(a b . c)",
+        SExp::sym("c").cons(SExp::sym("b")).cons(SExp::sym("a")),
+    );
+    // a dotted null is just a proper list
+    do_parse_and_assert("(a . ())", Null.cons(SExp::sym("a")));
+
+    assert!("(. a)".parse::<SExp>().is_err());
+    assert!("(a .)".parse::<SExp>().is_err());
+    assert!("(a . b c)".parse::<SExp>().is_err());
+    assert!("#(a . b)".parse::<SExp>().is_err());
+
+    // printing round-trips
+    for improper in &["(a . b)", "(a b . c)", "((a . b) c . d)"] {
+        assert_eq!(improper.parse::<SExp>().unwrap().to_string(), *improper);
+    }
+    assert!("(a b . c)".parse::<SExp>().unwrap().len().is_err());
+    assert_eq!("(a b . ())".parse::<SExp>().unwrap().len().unwrap(), 2);
+}
+
 #[test]
 fn quote_syntax() {
     do_parse_and_assert(
@@ -103,6 +211,23 @@ fn quote_syntax() {
         "'potato",
         Null.cons(SExp::sym("potato")).cons(SExp::sym("quote")),
     );
+
+    // only a two-element quote form prints abbreviated
+    for (quoted, printed) in &[
+        ("''a", "'a"),
+        ("'(quote . a)", "(quote . a)"),
+        ("'(quote a b)", "(quote a b)"),
+        ("'(quote)", "(quote)"),
+    ] {
+        let expr = quoted
+            .parse::<SExp>()
+            .unwrap()
+            .cdr()
+            .unwrap()
+            .car()
+            .unwrap();
+        assert_eq!(expr.to_string(), *printed);
+    }
 }
 
 #[test]
@@ -129,6 +254,18 @@ fn quasiquote_syntax() {
         )
         .cons(SExp::sym("quasiquote")),
     );
+
+    do_parse_and_assert(
+        "`(a ,@b)",
+        Null.cons(
+            Null.cons(
+                Null.cons(SExp::sym("b"))
+                    .cons(SExp::sym("unquote-splicing")),
+            )
+            .cons(SExp::sym("a")),
+        )
+        .cons(SExp::sym("quasiquote")),
+    );
 }
 
 mod parens {
@@ -13,6 +13,7 @@ pub fn is_symbol_char(c: char) -> bool {
     c.is_alphanumeric()
         || c == '-'
         || c == '_'
+        || c == '.'
         || c == '?'
         || c == '!'
         || c == '*'
@@ -64,6 +64,10 @@ def_test! {
         [FILE "lambda.ss"]
         ["(reverse-subtract 7 10)", 3]
         ["(foo 6)", 10]
+
+        [EXPR "((lambda x x) 3 4 5 6)", "(3 4 5 6)"]
+        [EXPR "((lambda (x y . z) z) 3 4 5 6)", "(5 6)"]
+        [IS_ERR "((lambda (x y . z) z) 3)"]
 }
 
 def_test! {
@@ -86,6 +90,11 @@ def_test! {
 def_test! {
     letrec
         [FILE "letrec.ss", true]
+        [IS_ERR "(letrec ((a 1) (b (+ a 1))) b)"]
+        ["(letrec* ((a 1) (b (+ a 1))) b)", 2]
+        [IS_ERR "(letrec* ((a b) (b 1)) a)"]
+        [EXPR "(guard (e (#t (error-object-irritants e))) (letrec ((a b) (b 1)) a))", "(b)"]
+        ["(guard (e (#t (error-object-message e))) (letrec ((a b) (b 1)) a))", "Unassigned variable:"]
 }
 
 def_test! {
@@ -98,12 +107,30 @@ def_test! {
 
         "(define bar)"
         [IS_ERR "bar"]
+
+        "(define (rest first . others) others)"
+        [EXPR "(rest 1)", "()"]
+        [EXPR "(rest 'a 'b 'c)", "(b c)"]
 }
 
 def_test! {
     define_internal
         [FILE "define.ss", 45]
         [FILE "define_letrec.ss", 45]
+
+        "(define (f) (define a b) (define b 1) a)"
+        [IS_ERR "(f)"]
+        "(define (g n) (define (ev? n) (if (= n 0) #t (od? (- n 1)))) (define (od? n) (if (= n 0) #f (ev? (- n 1)))) (ev? n))"
+        ["(g 10)", true]
+        "(define (h x) (define y (* x 2)) (define z) (set! z 3) (+ y z))"
+        ["(h 5)", 13]
+
+        // later definitions are in scope for the whole body
+        "(define x 'outer)"
+        "(define (k) (define a 1) (set! a x) (define x 'inner) a)"
+        [IS_ERR "(k)"]
+        "(define (m) (set! x 'reset) (define (sq n) (* n n)) (sq 4))"
+        ["(m)", 16]
 }
 
 def_test! {
@@ -139,33 +166,26 @@ def_test! {
 def_test! {
     quasiquote
         [EXPR "`(list ,(+ 1 2) 4)", "(list 3 4)"]
-    // FIXME: quote before quasiquote
-        //[EXPR "(let ((name 'a)) `(list ,name ',name))", "(list a 'a)"]
-    // FIXME: unquote-splicing
-        // [EXPR
-        //  "`(a ,(+ 1 2) ,@(map abs '(4 -5 6)) b)",
-        //  "(a 3 4 5 b)"
-        // ]
-    // FIXME: unquote-splicing
-        // [EXPR
-        //  "`((foo ,(- 10 3)) ,@(cdr '(c)) . ,(car '(cons)))",
-        //  "((foo 7) . cons)"
-        // ]
-    // FIXME: unquote-splicing
-        // [EXPR "`#(10 5 ,(sqrt 4) ,@(map sqrt '(16 9)) 8)", "#(10 5 2 4 3 8)"]
-    // FIXME: quasiquote with immediate unquote
-        // ["`,(+ 2 3)", 5]
-
-    // FIXME: nested quasiquote/unquote
-        // [EXPR
-        //  "`(a `(b ,(+ 1 2) ,(foo ,(+ 1 3) d) e) f)",
-        //  "(a `(b ,(+ 1 2) ,(foo 4 d) e) f)"
-        // ]
-    // FIXME: nested quasiquote/unquote
-        // [EXPR
-        //  "(let ((name1 'x) (name2 'y)) `(a `(b ,,name1 ,',name2 d) e))",
-        //  "(a `(b ,x ,'y d) e)"
-        // ]
+        [EXPR "(let ((name 'a)) `(list ,name ',name))", "(list a 'a)"]
+        [EXPR
+         "`(a ,(+ 1 2) ,@(map abs '(4 -5 6)) b)",
+         "(a 3 4 5 6 b)"
+        ]
+        [EXPR
+         "`((foo ,(- 10 3)) ,@(cdr '(c)) . ,(car '(cons)))",
+         "((foo 7) . cons)"
+        ]
+        [EXPR "`#(10 5 ,(sqrt 4) ,@(map sqrt '(16 9)) 8)", "#(10 5 2 4 3 8)"]
+        ["`,(+ 2 3)", 5]
+
+        [EXPR
+         "`(a `(b ,(+ 1 2) ,(foo ,(+ 1 3) d) e) f)",
+         "(a `(b ,(+ 1 2) ,(foo 4 d) e) f)"
+        ]
+        [EXPR
+         "(let ((name1 'x) (name2 'y)) `(a `(b ,,name1 ,',name2 d) e))",
+         "(a `(b ,x ,'y d) e)"
+        ]
 
         [EXPR "(quasiquote (list (unquote (+ 1 2)) 4))", "(list 3 4)"]
         [EXPR "'(quasiquote (list (unquote (+ 1 2)) 4))", "`(list ,(+ 1 2) 4)"]
@@ -182,8 +202,7 @@ def_test! {
     cond
         [FILE_EXPR "cond_1.ss", "greater"]
         [FILE_EXPR "cond_2.ss", "equal"]
-    // FIXME: arrow syntax for cond AND alists
-        // ["(cond ((assv 'b '((a 1) (b 2))) => cadr) (else #f))", 2]
+        ["(cond ((- 3 3) => (lambda (x) (+ x 20))) (else #f))", 20]
 }
 
 def_test! {
@@ -227,7 +246,224 @@ def_test! {
         [FILE "do_2.ss", 25]
 }
 
-// TODO: structs and macros
+def_test! {
+    pairs
+        [EXPR "(cons 'a 3)", "(a . 3)"]
+        [EXPR "(cons '(a b) 'c)", "((a b) . c)"]
+        [EXPR "(car '(a b . c))", "a"]
+        [EXPR "(cdr '(a b . c))", "(b . c)"]
+        [EXPR "'(a . (b . (c . ())))", "(a b c)"]
+        [IS_ERR "(map car '((a) . (b)))"]
+}
+
+def_test! {
+    call_cc
+        ["(call-with-current-continuation (lambda (exit) (+ 1 (exit 5))))", 5]
+        ["(+ 1 (call/cc (lambda (k) 2)))", 3]
+        [FILE "call_cc.ss"]
+        [EXPR "(list (gen) (gen) (gen) (gen))", "(1 2 3 done)"]
+        "(define r #f)"
+        ["(+ 1 (call/cc (lambda (k) (set! r k) 1)))", 2]
+        ["(r 5)", 6]
+        ["(r 10)", 11]
+        [IS_ERR "(r 1 2)"]
+        [EXPR "(call-with-values (lambda () (call/cc (lambda (k) (k 1 2)))) list)", "(1 2)"]
+        [EXPR "(call-with-values (lambda () (call/cc (lambda (k) (k)))) list)", "()"]
+        ["(receive (a b c) (call/cc (lambda (k) (k 1 2 3))) (+ a b c))", 6]
+
+        // builtins that call back into Scheme can't be re-entered once they
+        // have returned
+        "(define k #f)"
+        [EXPR "(vector-map (lambda (x) (call/cc (lambda (c) (set! k c) x))) #(1 2 3))", "#(1 2 3)"]
+        [IS_ERR "(k 10)"]
+        [EXPR "(sort '(3 1 2) (lambda (a b) (call/cc (lambda (c) (set! k c) (< a b)))))", "(1 2 3)"]
+        [IS_ERR "(k #t)"]
+        "(define t (make-equal-hash-table))"
+        "(hash-table-set! t 'a 1)"
+        "(hash-table-walk t (lambda (key v) (call/cc (lambda (c) (set! k c)))))"
+        [IS_ERR "(k 1)"]
+        "(hash-table-update! t 'a (lambda (v) (call/cc (lambda (c) (set! k c) v))))"
+        [IS_ERR "(k 2)"]
+        [r#"(string-index "abc" (lambda (ch) (call/cc (lambda (c) (set! k c) (eqv? ch #\b)))))"#, 1]
+        [IS_ERR "(k #t)"]
+        "(define-macro (one) (call/cc (lambda (c) (set! k c) 1)))"
+        ["(one)", 1]
+        [IS_ERR "(k 2)"]
+        // while they are running, escaping from them still works
+        ["(call/cc (lambda (out) (vector-map (lambda (x) (out x)) #(5 6))))", 5]
+}
+
+def_test! {
+    syntax_rules
+        [FILE "syntax_rules.ss"]
+        ["(let ((temp 8)) (my-or #f temp))", 8]
+        ["(my-or)", false]
+        ["(let ((x 1) (y 2)) (swap! x y) (- x y))", 1]
+        "(be-like-begin sequence)"
+        ["(sequence 1 2 3 4)", 4]
+        ["(let-syntax ((foo (syntax-rules () ((_ x) (* x 2))))) (foo 21))", 42]
+        [EXPR "(letrec-syntax ((my-and (syntax-rules () ((_) #t) ((_ e) e) ((_ e r ...) (if e (my-and r ...) #f))))) (list (my-and 1 2 3) (my-and 1 #f 3)))", "(3 #f)"]
+        [EXPR "(for x in '(1 2 3) (* x x))", "(1 4 9)"]
+        [IS_ERR "(for x over '(1 2 3) x)"]
+        [IS_ERR "(define-syntax five 5)"]
+
+        // definitions made by a template don't leak to the use site
+        "(define helper 1)"
+        "(define-syntax def-getter (syntax-rules () ((_ name) (begin (define helper 10) (define (name) helper)))))"
+        "(def-getter get)"
+        ["(get)", 10]
+        ["helper", 1]
+
+        // free identifiers in a template refer to the definition environment
+        "(define (double x) (* x 2))"
+        "(define-syntax dbl (syntax-rules () ((_ e) (double e))))"
+        ["(let ((double (lambda (x) 'oops))) (dbl 5))", 10]
+        "(define-syntax my-list (syntax-rules () ((_ e ...) (list e ...))))"
+        [EXPR "(let ((list +)) (my-list 1 2))", "(1 2)"]
+
+        // quoted data in a template is left alone
+        "(define-syntax tag (syntax-rules () ((_ e) (let ((tmp e)) (list 'tmp tmp)))))"
+        [EXPR "(tag 1)", "(tmp 1)"]
+        "(define-syntax qtag (syntax-rules () ((_ e) (let ((tmp e)) `(tmp ,tmp)))))"
+        [EXPR "(qtag 1)", "(tmp 1)"]
+}
+
+// TODO: structs
+
+def_test! {
+    rationals
+        [EXPR "(/ 1 3)", "1/3"]
+        [EXPR "(/ 6 4)", "3/2"]
+        ["(+ 1/3 2/3)", 1]
+        [EXPR "(- 1/2 1/3)", "1/6"]
+        [EXPR "(* 2/3 3/4)", "1/2"]
+        ["(/ 1/2 0.5)", 1.0]
+        ["(< 1/3 0.34)", true]
+        ["(= 2/4 1/2)", true]
+        ["(numerator 6/4)", 3]
+        ["(denominator 6/4)", 2]
+        ["(numerator -3/9)", -1]
+        ["(denominator 5)", 1]
+        ["(exact->inexact 1/4)", 0.25]
+        [EXPR "(inexact->exact 0.25)", "1/4"]
+        [EXPR "(inexact->exact 0.1)", "3602879701896397/36028797018963968"]
+        [IS_ERR "(inexact->exact (/ 1. 0))"]
+        ["(round 9223372036854775806/9223372036854775807)", 1]
+        ["(round -9223372036854775806/9223372036854775807)", -1]
+        ["(round -7/2)", -4]
+
+        [IS_ERR "(/ 1 0)"]
+        [IS_ERR "(/ 0)"]
+        [IS_ERR "(/ 1.5 2 0)"]
+        ["(/ 1 0.)", f64::INFINITY]
+        ["(guard (e (#t (condition/report-string e))) (/ 1/2 0))", "Division by zero signalled by /."]
+
+        [EXPR "(/ 1 (expt 2 70))", "1/1180591620717411303424"]
+        ["(exact? (/ 1 (expt 2 70)))", true]
+        [EXPR "(+ 1/3 (/ 1 (expt 3 40)))", "4052555153018976268/12157665459056928801"]
+        ["(* (/ 1 (expt 2 70)) (expt 2 70))", 1]
+        [EXPR "(- (/ (expt 10 30) 7))", "-1000000000000000000000000000000/7"]
+        [EXPR "(sqrt 9/4)", "3/2"]
+        [EXPR "(sqrt (expt 10 40))", "100000000000000000000"]
+        ["(exact? (sqrt (expt 10 40)))", true]
+        ["(exact? (sqrt 2))", false]
+        ["(floor (/ (expt 10 30) 7))", "142857142857142857142857142857".parse::<SExp>()?]
+        ["(round (/ (+ (expt 10 30) 1) 2))", "500000000000000000000000000000".parse::<SExp>()?]
+        ["(round (/ (+ (expt 10 30) 3) 2))", "500000000000000000000000000002".parse::<SExp>()?]
+        ["(exact->inexact (/ (expt 10 400) (+ 1 (expt 10 399))))", 10.0]
+}
+
+def_test! {
+    exactness
+        ["(exact? 1/2)", true]
+        ["(exact? 2.0)", false]
+        ["(inexact? 4.)", true]
+        ["(integer? 2.0)", true]
+        ["(integer? 5/2)", false]
+        ["(rational? 1.5)", true]
+        ["(rational? +inf.0)", false]
+        ["(rational? -inf.0)", false]
+        ["(rational? +nan.0)", false]
+        ["(rational? (/ 0. 0.))", false]
+        ["(real? 1+2i)", false]
+        ["(number? 'a)", false]
+        ["(exact-integer? 4.0)", false]
+        [EXPR "(exact 2.5)", "5/2"]
+        [EXPR "(inexact 1/4)", "0.25"]
+        [EXPR "(list (floor 2.5) (round 2.5) (round 7/2) (truncate -2.7))", "(2.0 2.0 4 -2.0)"]
+        ["(exact? (floor 2.5))", false]
+        ["(eqv? 2 2.0)", false]
+        ["(= 2 2.0)", true]
+}
+
+def_test! {
+    numeric_procedures
+        ["(- 3)", -3]
+        [EXPR "(/ 4)", "1/4"]
+        [EXPR "(list (quotient 17 5) (quotient -17 5))", "(3 -3)"]
+        [EXPR "(list (modulo -17 5) (remainder -17 5) (modulo 17 -5))", "(3 -2 -3)"]
+        [IS_ERR "(modulo 1 0)"]
+        [IS_ERR "(quotient 1.5 2)"]
+        ["(expt 2 10)", 1024]
+        [EXPR "(expt 2/3 -3)", "27/8"]
+        ["(expt 1 4294967296)", 1]
+        ["(expt -1 4294967297)", -1]
+        ["(expt 0 4294967296)", 0]
+        [IS_ERR "(expt 2 4294967296)"]
+        [IS_ERR "(expt 2 4294967297)"]
+        [IS_ERR "(expt 0 -1)"]
+        ["(expt 2. 4294967296)", f64::INFINITY]
+        ["(expt 2 0.5)", std::f64::consts::SQRT_2]
+        ["(gcd 12 18 27)", 3]
+        ["(gcd)", 0]
+        ["(lcm 4 6)", 12]
+        ["(exact? (lcm 4 6.0))", false]
+        ["(min 1 2.0)", 1.0]
+        ["(exact? (min 1 2.0))", false]
+        [EXPR "(max 3 1/2)", "3"]
+        ["(nan? (max 1 2 +nan.0))", true]
+        ["(nan? (min +nan.0 1))", true]
+        ["(<= 1 2 2 3)", true]
+        ["(>= 3 2 5)", false]
+        ["(< 1 2 3)", true]
+        ["(= 1 1.0 1)", true]
+        [r#"(number->string 255 16)"#, "ff"]
+        [r#"(number->string -10 2)"#, "-1010"]
+        [r#"(number->string 2.5)"#, "2.5"]
+        [IS_ERR "(number->string 2.5 2)"]
+        [r#"(string->number "ff" 16)"#, 255]
+        [r##"(string->number "#b101")"##, 5]
+        [EXPR r#"(string->number "1/3")"#, "1/3"]
+        [r#"(string->number "abc")"#, false]
+}
+
+def_test! {
+    complex
+        ["(= 3+4i (make-rectangular 3 4))", true]
+        ["(= -i (make-rectangular 0 -1))", true]
+        ["(make-rectangular 3 0)", 3]
+        ["(make-polar 2 0)", 2]
+        ["(real-part 3+4i)", 3]
+        ["(imag-part 3+4i)", 4]
+        ["(imag-part 3)", 0]
+        ["(magnitude 3+4i)", 5]
+        ["(magnitude -5)", 5]
+        ["(angle -1)", std::f64::consts::PI]
+        ["(angle +i)", std::f64::consts::FRAC_PI_2]
+        ["(* +i +i)", -1]
+        [EXPR "(+ 1+2i 3-3i)", "4-i"]
+        [EXPR "(/ 1+i 1-i)", "+i"]
+        ["(= 1+2i 1+2i)", true]
+        ["(= 1+2i 1-2i)", false]
+
+        // complex numbers are inexact-only, and unordered
+        ["(exact? 1+2i)", false]
+        ["(inexact? (sqrt -4))", true]
+        [IS_ERR "(exact 1+2i)"]
+        [IS_ERR "(< 1+i 2)"]
+        [IS_ERR "(>= 2 1 +i)"]
+        [IS_ERR "(max 1+i 2)"]
+}
 
 def_test! {
     eqv
@@ -235,6 +471,12 @@ def_test! {
         ["(eqv? 'a 'b)", false]
         ["(eqv? '() '())", true]
         ["(eqv? 100000000 100000000)", true]
+        ["(eqv? 2.5 2.5)", true]
+        ["(eqv? 0.0 -0.0)", false]
+        ["(eqv? 0.1 0.10000000000000002)", false]
+        ["(equal? '(0.1) '(0.10000000000000002))", false]
+        ["(eqv? 1+2i 1+2i)", true]
+        ["(eqv? 2 2.0)", false]
         ["(eqv? (cons 1 2) (cons 1 2))", false]
         ["(eqv? (lambda () 1) (lambda () 2))", false]
         ["(eqv? #f 'nil)", false]
@@ -258,8 +500,7 @@ def_test! {
                   (g (lambda () (if (eqv? f g) 'g 'both))))
            (eqv? f g))", false]
 
-    // FIXME: pointer comparisons for compound types
-        // ["(let ((x '(a))) (eqv? x x))", true]
+        ["(let ((x '(a))) (eqv? x x))", true]
         "(eqv? '(a) '(a))"
         r#" (eqv? "a" "a") "#
         "(eqv? '(b) (cdr '(a b)))"
@@ -269,8 +510,7 @@ def_test! {
     eq
         ["(eq? 'a 'a)", true]
         "(eq? '(a) '(a))"
-    // FIXME: pointer comparisons for lists
-        // ["(eq? (list 'a) (list 'a))", false]
+        ["(eq? (list 'a) (list 'a))", false]
         r#" (eq? "a" "a") "#
         r#" (eq? "" "") "#
         ["(eq? '() '())", true]
@@ -290,6 +530,473 @@ def_test! {
         ["(equal? '(a (b) c) '(a (b) c))", true]
         [r#" (equal? "abc" "abc") "#, true]
         ["(equal? 2 2)", true]
+        ["(equal? 1/2 0.5)", false]
+        ["(equal? '(1 2) '(1 2.0))", false]
+        ["(equal? #(1.5) #(1.5))", true]
         ["(equal? (make-vector 5 'a) (make-vector 5 'a))", true]
         "(equal? (lambda (x) x) (lambda (y) y))"
 }
+
+def_test! {
+    strings
+        [r#"(string-length "λόγος")"#, 5]
+        [r#"(string-ref "λόγος" 1)"#, 'ό']
+        [IS_ERR r#"(string-ref "abc" 3)"#]
+        [r#"(substring "arduous" 2 5)"#, "duo"]
+        [r#"(substring "arduous" 2)"#, "duous"]
+        [IS_ERR r#"(substring "arduous" 5 2)"#]
+        [r#"(string-append "foo" "λ" "bar")"#, "fooλbar"]
+        [r#"(string-copy "hello" 1 3)"#, "el"]
+        [r#"(string #\a #\b)"#, "ab"]
+        [r#"(make-string 3 #\x)"#, "xxx"]
+        [EXPR r#"(string->list "abc" 1)"#, r"(#\b #\c)"]
+        [r#"(string-upcase "Hello")"#, "HELLO"]
+        [r#"(string-downcase "Hello")"#, "hello"]
+
+        [r#"(string=? "abc" "abc" "abc")"#, true]
+        [r#"(string<? "abc" "abd" "b")"#, true]
+        [r#"(string>? "abc" "abd")"#, false]
+        [r#"(string<=? "abc" "abc")"#, true]
+        [r#"(string-ci=? "ABC" "abc")"#, true]
+        [r#"(string-ci<? "ABC" "abd")"#, true]
+
+        [r#"(string-index "hello" #\l)"#, 2]
+        [r#"(string-index "hello" (lambda (c) (eq? c #\o)))"#, 4]
+        [r#"(string-index "hello" #\z)"#, false]
+        [r#"(string-search-forward "rat" "pirate" 0)"#, 2]
+        [r#"(string-search-forward "rat" "pirate rating" 4)"#, 7]
+        [r#"(string-search-forward "λ" "aλbλ" 2)"#, 3]
+        [r#"(string-contains "pirate" "ate")"#, 3]
+        [r#"(string-contains "pirate" "cat")"#, false]
+        [EXPR r#"(string-split "a,b,,c" #\,)"#, r#"("a" "b" "" "c")"#]
+        [r#"(string-join '("a" "b" "c"))"#, "a b c"]
+        [r#"(string-join '("a" "b" "c") ", ")"#, "a, b, c"]
+        [r#"(string-pad-left "42" 5)"#, "   42"]
+        [r#"(string-pad-left "12345" 3)"#, "345"]
+        [r#"(string-pad-right "42" 4 #\*)"#, "42**"]
+        [r#"(string-pad-right "12345" 3)"#, "123"]
+
+        r#"(define s (make-string 3 #\a))"#
+        r#"(string-set! s 1 #\λ)"#
+        ["s", "aλa"]
+        r#"(string-fill! s #\z 2)"#
+        ["s", "aλz"]
+        [IS_ERR r#"(string-set! s 3 #\a)"#]
+
+        // strings are shared, not copied, by bindings and data structures
+        "(define t s)"
+        r#"(string-set! s 0 #\z)"#
+        ["t", "zλz"]
+        r#"(define l (list (string-copy "abc")))"#
+        r#"(string-set! (car l) 0 #\z)"#
+        ["(car l)", "zbc"]
+        r#"(define (clear! str) (string-fill! str #\-))"#
+        "(clear! t)"
+        ["s", "---"]
+        ["(eqv? s t)", true]
+        [r#"(eqv? s (string-copy s))"#, false]
+        [r#"(equal? s (string-copy s))"#, true]
+        [IS_ERR r#"(string-set! 'sym 0 #\a)"#]
+}
+
+def_test! {
+    characters
+        ["(char? #\\a)", true]
+        ["(char? \"a\")", false]
+        ["(char->integer #\\A)", 65]
+        ["(char->integer #\\space)", 32]
+        ["(integer->char 955)", 'λ']
+        ["(integer->char (char->integer #\\newline))", '\n']
+        [IS_ERR "(integer->char -1)"]
+        [IS_ERR "(integer->char 55296)"]
+        ["(char-upcase #\\a)", 'A']
+        ["(char-downcase #\\Λ)", 'λ']
+        ["(char-upcase #\\1)", '1']
+        ["(char-alphabetic? #\\a)", true]
+        ["(char-alphabetic? #\\1)", false]
+        ["(char-numeric? #\\1)", true]
+        ["(char-whitespace? #\\tab)", true]
+        ["(char-whitespace? #\\x)", false]
+        ["(char-upper-case? #\\A)", true]
+        ["(char-lower-case? #\\A)", false]
+        ["(digit-value #\\3)", 3]
+        ["(digit-value #\\a)", false]
+        ["(digit-value #\\x664)", 4]
+        ["(digit-value #\\x1d7d9)", 1]
+        ["(digit-value #\\x2163)", false]
+
+        ["(char=? #\\a #\\a #\\a)", true]
+        ["(char<? #\\a #\\b #\\c)", true]
+        ["(char<? #\\a #\\c #\\b)", false]
+        ["(char>=? #\\b #\\b #\\a)", true]
+        ["(char-ci=? #\\a #\\A)", true]
+        ["(char-ci<? #\\a #\\B)", true]
+}
+
+def_test! {
+    string_escapes
+        [r#"(string-length "a\nb")"#, 3]
+        [r#"(string-ref "a\tb" 1)"#, '\t']
+        [r#"(string->list "\x41;\x42;")"#, "(#\\A #\\B)".parse::<SExp>()?]
+        [r#"(string=? "a\
+               b" "ab")"#, true]
+}
+
+def_test! {
+    hash_tables
+        "(define h (make-equal-hash-table))"
+        ["(hash-table? h)", true]
+        ["(hash-table-count h)", 0]
+        "(hash-table-set! h 'a 1)"
+        "(hash-table-set! h '(x y) 2)"
+        "(hash-table-set! h \"str\" 3)"
+        ["(hash-table-ref h 'a)", 1]
+        ["(hash-table-ref h (list 'x 'y))", 2]
+        ["(hash-table-ref/default h \"str\" 0)", 3]
+        ["(hash-table-ref/default h 'missing 0)", 0]
+        [IS_ERR "(hash-table-ref h 'missing)"]
+        ["(hash-table-ref h 'missing (lambda () 'none))", "none".parse::<SExp>()?]
+        ["(hash-table-ref h 'a (lambda () 0) (lambda (v) (* v 10)))", 10]
+        ["(hash-table-count h)", 3]
+
+        "(hash-table-set! h 'a 5)"
+        ["(hash-table-count h)", 3]
+        "(hash-table-update! h 'a (lambda (v) (+ v 1)))"
+        ["(hash-table-ref h 'a)", 6]
+        "(hash-table-update! h 'b (lambda (v) (+ v 1)) (lambda () 0))"
+        ["(hash-table-ref h 'b)", 1]
+        "(hash-table-update!/default h 'c (lambda (v) (cons 'c v)) '())"
+        [EXPR "(hash-table-ref h 'c)", "(c)"]
+
+        "(hash-table-delete! h 'b)"
+        "(hash-table-delete! h 'c)"
+        "(hash-table-delete! h 'c)"
+        ["(hash-table-contains? h 'b)", false]
+        ["(hash-table-count h)", 3]
+        [EXPR "(hash-table-keys (let ((t (make-equal-hash-table))) (hash-table-set! t 'k 1) t))", "(k)"]
+        ["(apply + (hash-table-values h))", 11]
+        [EXPR "(hash-table->alist (let ((t (make-equal-hash-table))) (hash-table-set! t 'k 1) t))", "((k . 1))"]
+
+        "(define total 0)"
+        "(hash-table-walk h (lambda (k v) (set! total (+ total v))))"
+        ["total", 11]
+
+        "(define (add! table) (hash-table-set! table 'added #t))"
+        "(add! h)"
+        ["(hash-table-ref h 'added)", true]
+        ["(eq? h h)", true]
+        ["(equal? h (make-equal-hash-table))", false]
+
+        // numbers only match keys of the same exactness
+        "(define n (make-equal-hash-table))"
+        "(hash-table-set! n 1 'exact)"
+        "(hash-table-set! n 1.0 'inexact)"
+        "(hash-table-set! n '(2) 'exact-list)"
+        ["(hash-table-count n)", 3]
+        [EXPR "(hash-table-ref/default n 1 #f)", "exact"]
+        [EXPR "(hash-table-ref/default n 1.0 #f)", "inexact"]
+        ["(hash-table-ref/default n '(2.0) #f)", false]
+
+        "(define e (make-strong-eqv-hash-table))"
+        "(hash-table-set! e 1 'one)"
+        "(hash-table-set! e 'sym 'two)"
+        "(hash-table-set! e \"str\" 'three)"
+        [EXPR "(hash-table-ref/default e 1 #f)", "one"]
+        [EXPR "(hash-table-ref/default e 'sym #f)", "two"]
+        ["(hash-table-ref/default e 1.0 #f)", false]
+        ["(hash-table-ref/default e \"str\" #f)", false]
+
+        "(define key (list 1 2))"
+        "(define vkey (make-vector 2 0))"
+        "(define bkey (bytevector 1 2))"
+        "(hash-table-set! e key 'pair)"
+        "(hash-table-set! e vkey 'vector)"
+        "(hash-table-set! e bkey 'bytes)"
+        "(set-car! key 5)"
+        "(vector-set! vkey 0 5)"
+        "(bytevector-u8-set! bkey 0 5)"
+        [EXPR "(hash-table-ref/default e key #f)", "pair"]
+        [EXPR "(hash-table-ref/default e vkey #f)", "vector"]
+        [EXPR "(hash-table-ref/default e bkey #f)", "bytes"]
+        ["(hash-table-ref/default e (list 5 2) #f)", false]
+}
+
+def_test! {
+    vectors
+        "(define v (make-vector 3 0))"
+        "(define holder (list v))"
+        "(vector-set! (car holder) 0 'a)"
+        [EXPR "v", "#(a 0 0)"]
+        ["(eq? v (car holder))", true]
+        [IS_ERR "(vector-set! v 3 'x)"]
+
+        "(define c (vector-copy v))"
+        "(vector-fill! v 'z)"
+        [EXPR "c", "#(a 0 0)"]
+        [EXPR "v", "#(z z z)"]
+        "(vector-fill! c 1 1)"
+        [EXPR "c", "#(a 1 1)"]
+
+        ["(vector-length (vector-grow c 5))", 5]
+        [EXPR "(subvector (vector-grow c 5) 0 3)", "#(a 1 1)"]
+        [IS_ERR "(vector-grow c 2)"]
+
+        // indices must be exact, non-negative integers within range
+        [IS_ERR "(make-vector -1)"]
+        [IS_ERR "(make-vector 1.5)"]
+        [IS_ERR "(vector-ref c 1.5)"]
+        [IS_ERR "(vector-ref c 1.)"]
+        [IS_ERR "(subvector c 2 1)"]
+        [IS_ERR "(vector-head c 4)"]
+        [EXPR "(subvector c 1 3)", "#(1 1)"]
+        [EXPR "(vector-tail c 3)", "#()"]
+
+        "(define w '#(5 3 9 1))"
+        "(define alias w)"
+        "(sort! w <)"
+        [EXPR "alias", "#(1 3 5 9)"]
+        [EXPR "(sort '(3 1 2) >)", "(3 2 1)"]
+        "(define l (list 3 1 2))"
+        "(sort! l <)"
+        [EXPR "l", "(1 2 3)"]
+
+        ["(vector-binary-search w 5 -)", 5]
+        ["(vector-binary-search w 4 -)", false]
+}
+
+def_test! {
+    bytevectors
+        [EXPR "#u8(1 2 3)", "#u8(1 2 3)"]
+        ["(bytevector? (make-bytevector 2 7))", true]
+        [EXPR "(make-bytevector 2 7)", "#u8(7 7)"]
+        [EXPR "(bytevector 1 3 5)", "#u8(1 3 5)"]
+        [IS_ERR "(bytevector 256)"]
+
+        "(define bv (bytevector 1 2 3 4 5))"
+        "(define holder (list bv))"
+        ["(bytevector-length bv)", 5]
+        ["(bytevector-u8-ref bv 1)", 2]
+        [IS_ERR "(bytevector-u8-ref bv 5)"]
+        "(bytevector-u8-set! (car holder) 0 255)"
+        [EXPR "bv", "#u8(255 2 3 4 5)"]
+
+        [EXPR "(bytevector-copy bv 1 3)", "#u8(2 3)"]
+        "(bytevector-copy! bv 1 bv 0 2)"
+        [EXPR "bv", "#u8(255 255 2 4 5)"]
+        [IS_ERR "(bytevector-copy! bv 4 bv 0 2)"]
+        [EXPR "(bytevector-append #u8(0 1) #u8() #u8(2))", "#u8(0 1 2)"]
+
+        [EXPR r#"(string->utf8 "λx")"#, "#u8(206 187 120)"]
+        [EXPR r#"(string->utf8 "aλx" 1 2)"#, "#u8(206 187)"]
+        ["(utf8->string #u8(206 187 120))", "λx"]
+        ["(utf8->string #u8(65 66 67) 1)", "BC"]
+        [IS_ERR "(utf8->string #u8(255))"]
+}
+
+def_test! {
+    records
+        "(define-record-type point (make-point x y) point? (x point-x set-point-x!) (y point-y))"
+        "(define p (make-point 3 4))"
+        ["(point? p)", true]
+        ["(point? '#(3 4))", false]
+        ["(point-x p)", 3]
+        ["(point-y p)", 4]
+        "(set-point-x! p 10)"
+        ["(point-x p)", 10]
+        ["(type-of p)", "point"]
+        ["(eq? p p)", true]
+        ["(equal? p (make-point 10 4))", false]
+        [IS_ERR "(point-x 5)"]
+        [IS_ERR "(make-point 1)"]
+
+        "(define-record-type <node> (make-node value) node? (value node-value) (next node-next set-node-next!))"
+        "(define n (make-node 'a))"
+        ["(node-next n)", false]
+        "(set-node-next! n n)"
+        ["(eq? (node-next n) n)", true]
+        ["(type-of n)", "node"]
+        [IS_ERR "(node-value p)"]
+
+        "(define-record-type pare kons pare? (kar kar) (kdr kdr))"
+        ["(kdr (kons 1 2))", 2]
+}
+
+def_test! {
+    exceptions
+        [EXPR "(guard (e (#t (list 'caught e))) (raise 'boom))", "(caught boom)"]
+        ["(guard (e ((number? e) 1) ((string? e) 2)) (raise \"oops\"))", 2]
+        ["(guard (e ((error-object? e) (error-object-message e))) (error \"bad thing\" 1 2))", "bad thing"]
+        [EXPR "(guard (e (#t (error-object-irritants e))) (error \"bad thing\" 1 'b))", "(1 b)"]
+        ["(guard (e (#t (condition/report-string e))) (error \"bad thing:\" 1 \"two\"))", "bad thing: 1 \"two\""]
+        ["(guard (e ((error-object? e) #t)) (car 5))", true]
+        [EXPR "(guard (e (#t (error-object-irritants e))) undefined-thing)", "(undefined-thing)"]
+        [EXPR "(guard (e ((string? e) 'inner)) (guard (e ((number? e) 'outer)) (raise \"x\")))", "inner"]
+        [IS_ERR "(guard (e ((number? e) e)) (raise 'uncaught))"]
+        [IS_ERR "(raise 'uncaught)"]
+        [IS_ERR "(error \"uncaught\")"]
+        [EXPR "(map (lambda (i) (guard (e (#t 'err)) (vector-ref '#(a b) i))) '(0 5 1))", "(a err b)"]
+
+        ["(with-exception-handler (lambda (c) 42) (lambda () (+ (raise-continuable 'c) 1)))", 43]
+        ["(call/cc (lambda (k) (with-exception-handler (lambda (e) (k (* e 2))) (lambda () (raise 21)))))", 42]
+        [IS_ERR "(with-exception-handler (lambda (e) 0) (lambda () (raise 'not-continuable)))"]
+        ["(error-object? (guard (e (#t e)) (vector-ref '#(1) 5)))", true]
+        ["(error-object? 'boom)", false]
+        ["(file-error? (guard (e (#t e)) (error \"x\")))", false]
+}
+
+def_test! {
+    dynamic_wind
+        "(define trace '())"
+        "(define (note x) (set! trace (cons x trace)))"
+        ["(dynamic-wind (lambda () (note 'in)) (lambda () (note 'body) 5) (lambda () (note 'out)))", 5]
+        [EXPR "trace", "(out body in)"]
+
+        "(set! trace '())"
+        [EXPR "(call/cc (lambda (k) (dynamic-wind (lambda () (note 'in)) (lambda () (k 'escaped)) (lambda () (note 'out)))))", "escaped"]
+        [EXPR "trace", "(out in)"]
+
+        "(set! trace '())"
+        [EXPR "(guard (e (#t (note 'handled) e)) (dynamic-wind (lambda () (note 'in)) (lambda () (raise 'oops)) (lambda () (note 'out))))", "oops"]
+        [EXPR "trace", "(handled out in)"]
+
+        "(set! trace '())"
+        [IS_ERR "(dynamic-wind (lambda () (note 'a-in)) (lambda () (dynamic-wind (lambda () (note 'b-in)) (lambda () (map car '(1))) (lambda () (note 'b-out)))) (lambda () (note 'a-out)))"]
+        [EXPR "trace", "(a-out b-out b-in a-in)"]
+
+        "(set! trace '())"
+        "(define k2 #f)"
+        "(define n 0)"
+        ["(begin (dynamic-wind (lambda () (note 'in)) (lambda () (call/cc (lambda (k) (set! k2 k))) (set! n (+ n 1))) (lambda () (note 'out))) (if (< n 3) (k2 #f) n))", 3]
+        [EXPR "trace", "(out in out in out in)"]
+
+        // a continuation captured in tail position of the thunk is still
+        // inside the extent
+        "(set! trace '())"
+        "(set! n 0)"
+        ["(begin (dynamic-wind (lambda () (note 'in)) (lambda () (call/cc (lambda (k) (set! k2 k)))) (lambda () (note 'out))) (set! n (+ n 1)) (if (< n 3) (k2 #f) n))", 3]
+        [EXPR "trace", "(out in out in out in)"]
+
+        [IS_ERR "(dynamic-wind 1 (lambda () 2) (lambda () 3))"]
+}
+
+def_test! {
+    multiple_values
+        ["(call-with-values (lambda () (values 1 2)) +)", 3]
+        [EXPR "(call-with-values (lambda () 5) list)", "(5)"]
+        [EXPR "(call-with-values values list)", "()"]
+        [EXPR "(values 'a)", "a"]
+        [EXPR "(receive (a b . rest) (values 1 2 3 4) (list a b rest))", "(1 2 (3 4))"]
+        [EXPR "(receive all (values 1 2) all)", "(1 2)"]
+        [IS_ERR "(receive (a b) (values 1) a)"]
+
+        [EXPR "(let ((a 10)) (let-values (((a b) (values 1 2)) ((c) (values a))) (list a b c)))", "(1 2 10)"]
+        [EXPR "(let*-values (((a b) (values 1 2)) ((c . d) (values a b))) (list a b c d))", "(1 2 1 (2))"]
+
+        "(define-values (q r) (values 7 8))"
+        [EXPR "(list q r)", "(7 8)"]
+        "(define-values (x . y) (values 1 2 3))"
+        [EXPR "y", "(2 3)"]
+
+        "(define (count-down n) (if (= n 0) (values 'done n) (call-with-values (lambda () (values (- n 1))) count-down)))"
+        [EXPR "(receive (tag n) (count-down 100000) (list tag n))", "(done 0)"]
+
+        // several values can only go where they are expected
+        [IS_ERR "(list (values 1 2))"]
+        [IS_ERR "(if (values #f #f) 1 2)"]
+        [IS_ERR "((values car cdr) '(1))"]
+        [IS_ERR "(define z (values 1 2))"]
+        [IS_ERR "(vector-map (lambda (x) (values x x)) #(1))"]
+        [EXPR "(call-with-values (lambda () (dynamic-wind (lambda () 0) (lambda () (values 1 2)) (lambda () 0))) list)", "(1 2)"]
+        [EXPR "(call-with-values (lambda () (begin (values 1 2) (values 3 4))) list)", "(3 4)"]
+        [EXPR "(call-with-values (lambda () (parameterize () (values 1 2))) list)", "(1 2)"]
+        [EXPR "(call-with-values (lambda () (with-exception-handler (lambda (e) 0) (lambda () (values 1 2)))) list)", "(1 2)"]
+        [EXPR "(call-with-values (lambda () (guard (e (#t 0)) (values 1 2))) list)", "(1 2)"]
+}
+
+def_test! {
+    promises
+        "(define count 0)"
+        "(define p (delay (begin (set! count (+ count 1)) (* 6 7))))"
+        ["(promise? p)", true]
+        ["(force p)", 42]
+        ["(force p)", 42]
+        ["count", 1]
+        ["(force 5)", 5]
+        ["(force (make-promise 3))", 3]
+        ["(promise? (make-promise p))", true]
+
+        "(define (loop n) (if (= n 0) (make-promise 'done) (delay-force (loop (- n 1)))))"
+        [EXPR "(force (loop 100000))", "done"]
+        [IS_ERR "(force (delay-force 5))"]
+
+        "(define x 5)"
+        "(set! count 0)"
+        "(define r (delay (begin (set! count (+ count 1)) (if (> count x) count (force r)))))"
+        ["(force r)", 6]
+
+        "(define (from n) (cons-stream n (from (+ n 1))))"
+        [EXPR "(stream-head (from 3) 4)", "(3 4 5 6)"]
+        ["(stream-car (stream-tail (from 0) 100000))", 100000]
+        ["(stream-pair? (from 0))", true]
+        ["(stream-pair? '(1 2))", false]
+        ["(stream-null? the-empty-stream)", true]
+        [IS_ERR "(stream-cdr '(1 2))"]
+
+        [EXPR "(map car '((1 2) (3 4)))", "(1 3)"]
+        [EXPR "(apply list '((1) (2)))", "((1) (2))"]
+        [EXPR "(foldl (lambda (acc x) (cons x acc)) '() '((1) (2)))", "((2) (1))"]
+}
+
+def_test! {
+    derived_syntax
+        [EXPR "(when (> 2 1) 'a 'b)", "b"]
+        "(define hits 0)"
+        "(unless (> 2 1) (set! hits 1))"
+        ["hits", 0]
+        [EXPR "(unless #f 'c)", "c"]
+
+        ["(cond ((- 3 3) => (lambda (x) (+ x 20))) (else #f))", 20]
+        ["(cond (#f 1) ((+ 2 3)))", 5]
+        [EXPR "(case (* 2 3) ((2 3 5 7) 'prime) ((1 4 6 8 9) => (lambda (x) (list 'composite x))))", "(composite 6)"]
+        ["(case 10 ((1) 'one) (else => (lambda (x) (* x x))))", 100]
+        ["(guard (e ((and (number? e) e) => (lambda (n) (* n 2)))) (raise 21))", 42]
+        ["(guard (e ((number? e) 0) ((car e))) (raise (list 7)))", 7]
+
+        "(define plus (case-lambda ((a) a) ((a b) (+ a b)) ((a b . rest) (apply plus (cons (+ a b) rest)))))"
+        ["(plus 1)", 1]
+        ["(plus 1 2)", 3]
+        ["(plus 1 2 3 4)", 10]
+        [IS_ERR "(plus)"]
+        [EXPR "(procedure-arity plus)", "(1 . #f)"]
+
+        "(define radix (make-parameter 10 (lambda (x) (if (number? x) x (raise 'not-a-number)))))"
+        ["(radix)", 10]
+        ["(parameterize ((radix 2)) (radix))", 2]
+        ["(radix)", 10]
+        ["(parameterize ((radix 2)) (parameterize ((radix 16)) (radix)))", 16]
+        [IS_ERR "(parameterize ((radix 'x)) (radix))"]
+        ["(call/cc (lambda (k) (parameterize ((radix 8)) (k (radix)))))", 8]
+        ["(radix)", 10]
+        [IS_ERR "(parameterize ((car 1)) 2)"]
+
+        "(define depth 0)"
+        "(define (get-depth) depth)"
+        ["(fluid-let ((depth 1)) (get-depth))", 1]
+        ["depth", 0]
+        ["(call/cc (lambda (k) (fluid-let ((depth 2)) (k (get-depth)))))", 2]
+        ["depth", 0]
+        [IS_ERR "(fluid-let ((unbound-var 1)) 2)"]
+        [IS_ERR "(fluid-let ((depth 1)) (car '()))"]
+        ["depth", 0]
+        // re-entering the extent assigns the variable again before it is
+        // restored on the way out
+        "(define k #f)"
+        "(define n 0)"
+        "(define seen '())"
+        "(begin (fluid-let ((depth 5)) (call/cc (lambda (c) (set! k c)))) (set! seen (cons depth seen)) (set! n (+ n 1)) (if (< n 3) (k #f) #f))"
+        [EXPR "seen", "(0 0 0)"]
+
+        "(assert (= 1 1))"
+        [IS_ERR "(assert (= 1 2))"]
+        ["(guard (e (#t (condition/report-string e))) (assert #f 'x))", "Assertion failed x"]
+}
@@ -116,3 +116,41 @@ fn sicp_1_11() -> Result<(), Error> {
 }
 
 // TODO: Exercises 1.12 through 1.15
+
+// Section 1.2.1
+#[test]
+fn sicp_1_2_1() -> Result<(), Error> {
+    let mut ctx = Context::base();
+    ctx.run(include_str!("./sicp/ch1/factorial.ss"))?;
+
+    assert_eq!(ctx.run("(factorial 6)")?, SExp::from(720));
+    assert_eq!(
+        ctx.run("(factorial 100)")?.to_string(),
+        "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941463976156518286253697920827223758251185210916864000000000000000000000000"
+    );
+    assert_eq!(
+        ctx.run("(/ (factorial 100) (factorial 98))")?,
+        SExp::from(9900)
+    );
+
+    Ok(())
+}
+
+// Exercise 1.16
+#[test]
+fn sicp_1_16() -> Result<(), Error> {
+    let mut ctx = Context::base();
+    ctx.run(include_str!("./sicp/ch1/ex_16.ss"))?;
+
+    assert_eq!(ctx.run("(fast-expt 3 5)")?, SExp::from(243));
+    assert_eq!(
+        ctx.run("(fast-expt 2 100)")?.to_string(),
+        "1267650600228229401496703205376"
+    );
+    assert_eq!(
+        ctx.run("(fast-expt 2 100)")?,
+        ctx.run("1267650600228229401496703205376")?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use parsley::prelude::*;
+use parsley::Error;
+
+#[test]
+fn sicp_3_3_2() -> Result<(), Error> {
+    let mut ctx = Context::base();
+    ctx.run(include_str!("sicp/ch3/queue.ss"))?;
+
+    ctx.run("(define q (make-queue))")?;
+    assert_eq!(ctx.run("(empty-queue? q)")?, SExp::from(true));
+
+    ctx.run("(insert-queue! q 'a)")?;
+    ctx.run("(insert-queue! q 'b)")?;
+    ctx.run("(insert-queue! q 'c)")?;
+    assert_eq!(ctx.run("(front-ptr q)")?, ctx.run("'(a b c)")?);
+    assert_eq!(ctx.run("(rear-ptr q)")?, ctx.run("'(c)")?);
+
+    ctx.run("(delete-queue! q)")?;
+    assert_eq!(ctx.run("(front-queue q)")?, SExp::sym("b"));
+    assert_eq!(ctx.run("(front-ptr q)")?, ctx.run("'(b c)")?);
+
+    Ok(())
+}
+
+#[test]
+fn sicp_3_5_1() -> Result<(), Error> {
+    let mut ctx = Context::base();
+    ctx.run(include_str!("sicp/ch3/streams.ss"))?;
+
+    // only the elements that are needed get computed
+    ctx.run("(define seen '())")?;
+    ctx.run("(define (show x) (set! seen (cons x seen)) x)")?;
+    ctx.run("(define x (stream-map show (stream-enumerate-interval 0 10)))")?;
+    assert_eq!(ctx.run("(stream-ref x 5)")?, SExp::from(5));
+    assert_eq!(ctx.run("(stream-ref x 7)")?, SExp::from(7));
+    assert_eq!(ctx.run("seen")?, ctx.run("'(7 6 5 4 3 2 1 0)")?);
+
+    assert_eq!(
+        ctx.run("(stream-car (stream-cdr (stream-filter even? (stream-enumerate-interval 10000 1000000))))")?,
+        SExp::from(10002)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sicp_3_5_2() -> Result<(), Error> {
+    let mut ctx = Context::base();
+    ctx.run(include_str!("sicp/ch3/streams.ss"))?;
+
+    assert_eq!(ctx.run("(stream-ref primes 50)")?, SExp::from(233));
+    assert_eq!(
+        ctx.run("(stream-head fibs 10)")?,
+        ctx.run("'(0 1 1 2 3 5 8 13 21 34)")?
+    );
+    assert_eq!(ctx.run("(stream-ref double 10)")?, SExp::from(1024));
+    assert_eq!(
+        ctx.run("(stream-head (add-streams ones integers) 3)")?,
+        ctx.run("'(2 3 4)")?
+    );
+
+    Ok(())
+}